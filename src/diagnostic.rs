@@ -0,0 +1,348 @@
+//! This module contains diagnostics and their rendering as source snippets.
+
+use std::fmt::{self, Write};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::interp::RuntimeError;
+use crate::lexer::{LexError, LexErrorKind, Span};
+use crate::parser::ParseError;
+use crate::source::{Encoding, LineIndex};
+
+/// How serious a `Diagnostic` is.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// Message attached to a span of the input source.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Label {
+    span: Span,
+    message: String,
+    /// Primary labels point at the cause of a diagnostic, while secondary
+    /// labels provide additional context.
+    primary: bool,
+}
+
+/// Problem found in the input source, along with the spans it refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+}
+
+impl Severity {
+    /// Returns the ANSI escape code used to colour the severity.
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Note => "1;36",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note => "note",
+            }
+        )
+    }
+}
+
+impl Label {
+    /// Constructs a label pointing at the cause of a diagnostic.
+    pub fn primary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: true,
+        }
+    }
+
+    /// Constructs a label providing additional context to a diagnostic.
+    pub fn secondary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: false,
+        }
+    }
+
+    /// Returns the span the label points at.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the label's message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns `true` if the label points at the cause of a diagnostic.
+    pub fn is_primary(&self) -> bool {
+        self.primary
+    }
+}
+
+impl Diagnostic {
+    /// Constructs a diagnostic with the given severity and message.
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Constructs an error diagnostic.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Constructs a warning diagnostic.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Constructs a note diagnostic.
+    pub fn note(message: impl Into<String>) -> Self {
+        Self::new(Severity::Note, message)
+    }
+
+    /// Attaches a label to the diagnostic.
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Attaches a note, rendered after the source snippet.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Returns the severity of the diagnostic.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the diagnostic's message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the labels attached to the diagnostic.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+}
+
+impl From<&LexError> for Diagnostic {
+    fn from(error: &LexError) -> Self {
+        let label = match error.kind() {
+            LexErrorKind::UnterminatedString => "unterminated string starting here",
+            LexErrorKind::UnterminatedChar => "unterminated character starting here",
+            LexErrorKind::InvalidChar => "use a string literal for zero or multiple characters",
+            LexErrorKind::UnterminatedComment => "unterminated block comment starting here",
+            LexErrorKind::InvalidEscape(_) => "invalid escape sequence",
+            LexErrorKind::InvalidUnicodeEscape => {
+                "expected 1 to 6 hex digits in braces, as in `\\u{7FFF}`"
+            }
+            LexErrorKind::MalformedNumber => "not a valid number",
+            LexErrorKind::MissingExponent => "expected digits after the exponent",
+            LexErrorKind::InvalidSuffix => {
+                "expected `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64`, `f32` or `f64`"
+            }
+            LexErrorKind::UnexpectedCharacter(_) => "not a valid token",
+        };
+        Diagnostic::error(error.to_string()).with_label(Label::primary(error.span(), label))
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let label = match error.expected() {
+            [] => String::new(),
+            [kind] => format!("expected `{kind}`"),
+            kinds => {
+                let kinds = kinds
+                    .iter()
+                    .map(|kind| format!("`{kind}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("expected one of {kinds}")
+            }
+        };
+        Diagnostic::error(error.message()).with_label(Label::primary(error.span(), label))
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(error: &RuntimeError) -> Self {
+        Diagnostic::error(error.message()).with_label(Label::primary(error.span(), ""))
+    }
+}
+
+/// Renders diagnostics as source snippets, with the labelled spans underlined.
+///
+/// ```text
+/// error: Found `}`, but expected: `;`
+///  --> main.tr:2:10
+///   |
+/// 2 |     x = 1 }
+///   |          ^ expected `;`
+/// ```
+pub struct Renderer<'input> {
+    /// The name of the input source, such as its file path.
+    name: &'input str,
+    input: &'input str,
+    index: LineIndex,
+    color: bool,
+}
+
+impl<'input> Renderer<'input> {
+    /// Constructs a renderer for diagnostics referring to `input`.
+    pub fn new(name: &'input str, input: &'input str) -> Self {
+        Self {
+            name,
+            input,
+            index: LineIndex::new(input),
+            color: false,
+        }
+    }
+
+    /// Enables or disables ANSI colours in the rendered output.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Renders a diagnostic, ending with a newline.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        let severity = diagnostic.severity;
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(&format!("{severity}:"), severity.color()),
+            self.paint(&format!(" {}", diagnostic.message), "1"),
+        );
+
+        // Lines touched by any label, in order.
+        let mut lines = diagnostic
+            .labels
+            .iter()
+            .flat_map(|label| {
+                let (start, end) = self.label_lines(label);
+                start..=end
+            })
+            .collect::<Vec<_>>();
+        lines.sort_unstable();
+        lines.dedup();
+
+        let width = lines.last().map_or(0, |line| line.to_string().len());
+        let gutter = self.paint(&format!("{:width$} |", ""), "1;34");
+        if let Some(label) = diagnostic
+            .labels
+            .iter()
+            .find(|label| label.primary)
+            .or_else(|| diagnostic.labels.first())
+        {
+            // Columns count characters, matching how the source is displayed.
+            let start = self.index.line_col(label.span.start(), Encoding::Utf32);
+            let arrow = self.paint("-->", "1;34");
+            let _ = writeln!(out, "{:width$}{arrow} {}:{start}", "", self.name);
+            let _ = writeln!(out, "{gutter}");
+        }
+
+        let mut previous = None;
+        for &line in &lines {
+            if previous.is_some_and(|previous| line > previous + 1) {
+                let _ = writeln!(out, "{}", self.paint("...", "1;34"));
+            }
+            previous = Some(line);
+
+            let (start, end) = self.index.line_bounds(line).unwrap();
+            let text = &self.input[start..end];
+            let number = self.paint(&format!("{line:>width$} |"), "1;34");
+            let _ = writeln!(out, "{number} {}", expand_tabs(text));
+
+            for label in &diagnostic.labels {
+                let (first, last) = self.label_lines(label);
+                if !(first..=last).contains(&line) {
+                    continue;
+                }
+
+                // The part of the label's span on this line.
+                let from = label.span.start().clamp(start, end);
+                let to = label.span.end().clamp(start, end);
+                // Wide characters, such as those of CJK scripts, take up two
+                // columns of the terminal.
+                let offset = expand_tabs(&self.input[start..from]).width();
+                let len = expand_tabs(&self.input[from..to]).width().max(1);
+                let (mark, color) = if label.primary {
+                    ('^', severity.color())
+                } else {
+                    ('-', "1;34")
+                };
+
+                let mut underline = mark.to_string().repeat(len);
+                if line == last && !label.message.is_empty() {
+                    underline = format!("{underline} {}", label.message);
+                }
+                let _ = writeln!(
+                    out,
+                    "{gutter} {:offset$}{}",
+                    "",
+                    self.paint(&underline, color)
+                );
+            }
+        }
+
+        for note in &diagnostic.notes {
+            let _ = writeln!(out, "{:width$} {} {note}", "", self.paint("= note:", "1"));
+        }
+        out
+    }
+
+    /// Returns the first and last 1-based lines covered by a label.
+    fn label_lines(&self, label: &Label) -> (usize, usize) {
+        let span = label.span;
+        let first = self.index.line_col(span.start(), Encoding::Utf8).line;
+        // A span ending just after a newline does not touch the next line.
+        let end = if span.is_empty() {
+            span.end()
+        } else {
+            span.end() - 1
+        };
+        let last = self.index.line_col(end, Encoding::Utf8).line;
+        (first, last)
+    }
+
+    /// Wraps `text` in the ANSI escape code `style`, if colours are enabled.
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("\x1b[{style}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Replaces tabs with spaces so underlines line up with the rendered text.
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
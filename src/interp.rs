@@ -0,0 +1,414 @@
+//! This module contains a tree-walking interpreter, which evaluates the AST.
+
+use std::rc::Rc;
+
+pub use environment::{EnvRef, Environment};
+pub use error::{RuntimeError, RuntimeResult};
+pub use value::{Builtin, Function, Value};
+
+use crate::lexer::{Kind, Span};
+use crate::parser::ast;
+use crate::tk;
+
+mod builtins;
+mod environment;
+mod error;
+mod value;
+
+/// Maximum nesting depth of the statements and expressions being executed,
+/// including those of every function call in progress, beyond which a
+/// `RuntimeError` is returned rather than overflowing the stack.
+///
+/// Each level takes up to roughly 4 KiB of stack in debug builds, so the limit
+/// leaves room to spare on the 2 MiB stack of a spawned thread.
+const MAX_DEPTH: usize = 256;
+
+/// Tree-walking interpreter that evaluates statements and expressions.
+///
+/// Bindings persist across calls to `run`, `execute` and `evaluate`, so a
+/// single `Interpreter` may be fed a program piece by piece.
+pub struct Interpreter {
+    env: EnvRef,
+    /// Number of statements and expressions currently being executed.
+    depth: usize,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    /// Constructs an interpreter with the builtin functions and constants in
+    /// its global scope.
+    pub fn new() -> Self {
+        let env = Environment::new();
+        {
+            let mut globals = env.borrow_mut();
+            globals.define("true", Value::Bool(true));
+            globals.define("false", Value::Bool(false));
+            globals.define("inf", Value::Double(f64::INFINITY));
+            globals.define("nan", Value::Double(f64::NAN));
+            for builtin in builtins::definitions() {
+                globals.define(builtin.name, Value::Builtin(builtin));
+            }
+        }
+        Self { env, depth: 0 }
+    }
+
+    /// Looks up the value bound to `name` in the current scope.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.env.borrow().get(name)
+    }
+
+    /// Executes every statement of a program in order.
+    pub fn run(&mut self, program: &ast::Program) -> RuntimeResult<()> {
+        for stmt in &program.body {
+            self.execute(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// Executes a single statement.
+    ///
+    /// Expression statements return the value of their expression; all other
+    /// statements return `Value::Unit`.
+    pub fn execute(&mut self, stmt: &ast::Stmt) -> RuntimeResult<Value> {
+        self.nested(stmt.span, |interp| interp.execute_nested(stmt))
+    }
+
+    fn execute_nested(&mut self, stmt: &ast::Stmt) -> RuntimeResult<Value> {
+        match &stmt.kind {
+            ast::StmtKind::Let { var, value, .. } => {
+                let value = self.evaluate(value)?;
+                self.env.borrow_mut().define(var.clone(), value);
+            }
+            ast::StmtKind::Assignment { var, value } => {
+                let value = self.evaluate(value)?;
+                if !self.env.borrow_mut().assign(var, value) {
+                    return Err(RuntimeError::new(
+                        format!("Cannot assign to undefined variable: `{var}`"),
+                        stmt.span,
+                    ));
+                }
+            }
+            ast::StmtKind::If {
+                cond,
+                body,
+                else_stmt,
+            } => match self.evaluate(cond)? {
+                Value::Bool(true) => {
+                    let env = Environment::with_parent(self.env.clone());
+                    self.execute_block(body, env)?;
+                }
+                Value::Bool(false) => {
+                    if let Some(else_stmt) = else_stmt {
+                        self.execute(else_stmt)?;
+                    }
+                }
+                value => {
+                    return Err(RuntimeError::new(
+                        format!(
+                            "Expected a Bool condition, but found: {}",
+                            value.type_name()
+                        ),
+                        cond.span,
+                    ))
+                }
+            },
+            ast::StmtKind::Block { stmts } => {
+                let env = Environment::with_parent(self.env.clone());
+                self.execute_block(stmts, env)?;
+            }
+            ast::StmtKind::Item(ast::Item::Function(decl)) => {
+                let function = Function {
+                    decl: decl.clone(),
+                    env: self.env.clone(),
+                };
+                self.env
+                    .borrow_mut()
+                    .define(decl.name.clone(), Value::Function(Rc::new(function)));
+            }
+            // Structs only declare types, which are not checked at runtime.
+            ast::StmtKind::Item(ast::Item::Struct(_)) => {}
+            ast::StmtKind::Expr(expr) => return self.evaluate(expr),
+        }
+
+        Ok(Value::Unit)
+    }
+
+    /// Runs `run` one level of nesting deeper, failing with a `RuntimeError`
+    /// pointing at `span` instead once `MAX_DEPTH` levels are reached.
+    fn nested<T>(
+        &mut self,
+        span: Span,
+        run: impl FnOnce(&mut Self) -> RuntimeResult<T>,
+    ) -> RuntimeResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(RuntimeError::new("Maximum recursion depth exceeded", span));
+        }
+        self.depth += 1;
+        let result = run(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Executes statements within `env`, restoring the current scope after.
+    fn execute_block(&mut self, stmts: &[ast::Stmt], env: EnvRef) -> RuntimeResult<()> {
+        let previous = std::mem::replace(&mut self.env, env);
+        let result = stmts
+            .iter()
+            .try_for_each(|stmt| self.execute(stmt).map(drop));
+        self.env = previous;
+        result
+    }
+
+    /// Evaluates an expression to a value.
+    pub fn evaluate(&mut self, expr: &ast::Expr) -> RuntimeResult<Value> {
+        self.nested(expr.span, |interp| interp.evaluate_nested(expr))
+    }
+
+    fn evaluate_nested(&mut self, expr: &ast::Expr) -> RuntimeResult<Value> {
+        match &expr.kind {
+            ast::ExprKind::Literal(literal) => literal_value(literal, expr.span),
+            ast::ExprKind::Identifier(name) => self.get(name).ok_or_else(|| {
+                RuntimeError::new(format!("Undefined variable: `{name}`"), expr.span)
+            }),
+            ast::ExprKind::FunctionCall { name, args } => {
+                let callee = self.get(name).ok_or_else(|| {
+                    RuntimeError::new(format!("Undefined function: `{name}`"), expr.span)
+                })?;
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(arg))
+                    .collect::<RuntimeResult<Vec<_>>>()?;
+                self.call(callee, &args, expr.span)
+            }
+            ast::ExprKind::PrefixOperator { op, expr: operand } => {
+                let value = self.evaluate(operand)?;
+                prefix(*op, value, expr.span)
+            }
+            ast::ExprKind::InfixOperator {
+                op: op @ (tk![&&] | tk![||]),
+                lhs,
+                rhs,
+            } => {
+                // Logical operators short-circuit, so the right-hand side is
+                // only evaluated when needed.
+                let lhs = self.expect_bool(lhs, *op)?;
+                let short_circuit = if *op == tk![&&] { !lhs } else { lhs };
+                if short_circuit {
+                    return Ok(Value::Bool(lhs));
+                }
+                Ok(Value::Bool(self.expect_bool(rhs, *op)?))
+            }
+            ast::ExprKind::InfixOperator { op, lhs, rhs } => {
+                let lhs = self.evaluate(lhs)?;
+                let rhs = self.evaluate(rhs)?;
+                infix(*op, lhs, rhs, expr.span)
+            }
+            ast::ExprKind::PostfixOperator { op, expr: operand } => {
+                let value = self.evaluate(operand)?;
+                postfix(*op, value, expr.span)
+            }
+            ast::ExprKind::Interpolated(parts) => {
+                let mut string = String::new();
+                for part in parts {
+                    match part {
+                        ast::StringPart::Literal(literal) => string.push_str(literal),
+                        ast::StringPart::Expr(expr) => {
+                            string.push_str(&self.evaluate(expr)?.to_string())
+                        }
+                    }
+                }
+                Ok(Value::String(string))
+            }
+        }
+    }
+
+    /// Evaluates an operand of a logical operator, which must be a `Bool`.
+    fn expect_bool(&mut self, expr: &ast::Expr, op: Kind) -> RuntimeResult<bool> {
+        match self.evaluate(expr)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(RuntimeError::new(
+                format!("Cannot apply `{op}` to {}", value.type_name()),
+                expr.span,
+            )),
+        }
+    }
+
+    /// Calls a function value with already evaluated arguments.
+    fn call(&mut self, callee: Value, args: &[Value], span: Span) -> RuntimeResult<Value> {
+        match callee {
+            Value::Builtin(builtin) => (builtin.call)(args, span),
+            Value::Function(function) => {
+                let params = &function.decl.params;
+                if params.len() != args.len() {
+                    return Err(RuntimeError::new(
+                        format!(
+                            "`{}` expects {} argument(s), but found: {}",
+                            function.decl.name,
+                            params.len(),
+                            args.len()
+                        ),
+                        span,
+                    ));
+                }
+
+                let env = Environment::with_parent(function.env.clone());
+                for (param, arg) in params.iter().zip(args) {
+                    env.borrow_mut().define(param.name.clone(), arg.clone());
+                }
+                self.execute_block(&function.decl.body, env)?;
+                Ok(Value::Unit)
+            }
+            value => Err(RuntimeError::new(
+                format!("Cannot call a value of type {}", value.type_name()),
+                span,
+            )),
+        }
+    }
+}
+
+/// Converts a literal into its runtime value.
+///
+/// Only integer literals too large for an `i64` can fail to convert, as they
+/// are not supported at runtime.
+fn literal_value(literal: &ast::Literal, span: Span) -> RuntimeResult<Value> {
+    Ok(match literal {
+        ast::Literal::Integer(value) => Value::Integer(*value),
+        #[cfg(feature = "bigint")]
+        ast::Literal::BigInteger(value) => {
+            return Err(RuntimeError::new(
+                format!("Integer literal out of range: `{value}` does not fit in 64 bits"),
+                span,
+            ))
+        }
+        ast::Literal::SuffixedInteger { value, suffix } => match i64::try_from(*value) {
+            Ok(value) => Value::Integer(value),
+            Err(_) => {
+                return Err(RuntimeError::new(
+                    format!(
+                        "Integer literal out of range: `{value}{suffix}` does not fit in 64 bits"
+                    ),
+                    span,
+                ))
+            }
+        },
+        ast::Literal::Double(value) | ast::Literal::SuffixedDouble { value, .. } => {
+            Value::Double(*value)
+        }
+        ast::Literal::String(value) => Value::String(value.clone()),
+        ast::Literal::Char(value) => Value::Char(*value),
+    })
+}
+
+/// Builds the error for an operator applied to operands of the wrong type.
+fn type_error(op: Kind, operands: &[&Value], span: Span) -> RuntimeError {
+    let types = operands
+        .iter()
+        .map(|value| value.type_name())
+        .collect::<Vec<_>>()
+        .join(" and ");
+    RuntimeError::new(format!("Cannot apply `{op}` to {types}"), span)
+}
+
+/// Builds the error for integer arithmetic that overflows.
+fn overflow(op: Kind, span: Span) -> RuntimeError {
+    RuntimeError::new(format!("Integer overflow in `{op}`"), span)
+}
+
+fn prefix(op: Kind, value: Value, span: Span) -> RuntimeResult<Value> {
+    match (op, &value) {
+        (tk![+], Value::Integer(_) | Value::Double(_)) => Ok(value),
+        (tk![-], Value::Integer(n)) => n
+            .checked_neg()
+            .map(Value::Integer)
+            .ok_or_else(|| overflow(op, span)),
+        (tk![-], Value::Double(n)) => Ok(Value::Double(-n)),
+        (tk![!], Value::Bool(b)) => Ok(Value::Bool(!b)),
+        _ => Err(type_error(op, &[&value], span)),
+    }
+}
+
+fn postfix(op: Kind, value: Value, span: Span) -> RuntimeResult<Value> {
+    match (op, &value) {
+        (tk![!], Value::Integer(n)) if *n >= 0 => (1..=*n)
+            .try_fold(1i64, |acc, i| acc.checked_mul(i))
+            .map(Value::Integer)
+            .ok_or_else(|| overflow(op, span)),
+        (tk![!], Value::Integer(_)) => Err(RuntimeError::new(
+            "Cannot take the factorial of a negative number",
+            span,
+        )),
+        _ => Err(type_error(op, &[&value], span)),
+    }
+}
+
+fn infix(op: Kind, lhs: Value, rhs: Value, span: Span) -> RuntimeResult<Value> {
+    match (op, &lhs, &rhs) {
+        (tk![==], _, _) => Ok(Value::Bool(lhs == rhs)),
+        (tk![!=], _, _) => Ok(Value::Bool(lhs != rhs)),
+        // String concatenation
+        (tk![+], Value::String(a), _) => Ok(Value::String(format!("{a}{rhs}"))),
+        (tk![+], _, Value::String(b)) => Ok(Value::String(format!("{lhs}{b}"))),
+        (tk![<] | tk![>] | tk![<=] | tk![>=], Value::String(a), Value::String(b)) => {
+            Ok(Value::Bool(compare(op, a, b)))
+        }
+        (tk![<] | tk![>] | tk![<=] | tk![>=], Value::Char(a), Value::Char(b)) => {
+            Ok(Value::Bool(compare(op, a, b)))
+        }
+        // Integer arithmetic
+        (_, Value::Integer(a), Value::Integer(b)) => {
+            let (a, b) = (*a, *b);
+            let result = match op {
+                tk![+] => a.checked_add(b),
+                tk![-] => a.checked_sub(b),
+                tk![*] => a.checked_mul(b),
+                tk![/] if b == 0 => return Err(RuntimeError::new("Division by zero", span)),
+                tk![/] => a.checked_div(b),
+                // Negative exponents produce fractions, so fall back to doubles.
+                tk![^] if b < 0 => return Ok(Value::Double((a as f64).powf(b as f64))),
+                tk![^] => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+                tk![<] | tk![>] | tk![<=] | tk![>=] => return Ok(Value::Bool(compare(op, &a, &b))),
+                _ => return Err(type_error(op, &[&lhs, &rhs], span)),
+            };
+            result.map(Value::Integer).ok_or_else(|| overflow(op, span))
+        }
+        // Double arithmetic, promoting integers as needed
+        (_, Value::Integer(_) | Value::Double(_), Value::Integer(_) | Value::Double(_)) => {
+            let (a, b) = (as_double(&lhs), as_double(&rhs));
+            Ok(match op {
+                tk![+] => Value::Double(a + b),
+                tk![-] => Value::Double(a - b),
+                tk![*] => Value::Double(a * b),
+                tk![/] => Value::Double(a / b),
+                tk![^] => Value::Double(a.powf(b)),
+                tk![<] | tk![>] | tk![<=] | tk![>=] => Value::Bool(compare(op, &a, &b)),
+                _ => return Err(type_error(op, &[&lhs, &rhs], span)),
+            })
+        }
+        _ => Err(type_error(op, &[&lhs, &rhs], span)),
+    }
+}
+
+/// Applies a comparison operator to two ordered values.
+fn compare<T: PartialOrd + ?Sized>(op: Kind, a: &T, b: &T) -> bool {
+    match op {
+        tk![<] => a < b,
+        tk![>] => a > b,
+        tk![<=] => a <= b,
+        tk![>=] => a >= b,
+        _ => unreachable!("Token kind is not a comparison operator. {:?}", op),
+    }
+}
+
+/// Converts a numeric value to a double.
+fn as_double(value: &Value) -> f64 {
+    match value {
+        Value::Integer(n) => *n as f64,
+        Value::Double(n) => *n,
+        _ => unreachable!("Value is not numeric. {:?}", value),
+    }
+}
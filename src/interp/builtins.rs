@@ -0,0 +1,44 @@
+//! Functions provided natively by the interpreter.
+
+use super::{Builtin, RuntimeError, RuntimeResult, Value};
+use crate::lexer::Span;
+
+/// Provides the builtin functions to the interpreter's global scope.
+pub(crate) fn definitions() -> Vec<Builtin> {
+    vec![
+        Builtin {
+            name: "print",
+            call: print,
+        },
+        Builtin {
+            name: "len",
+            call: len,
+        },
+    ]
+}
+
+/// Writes its arguments to standard output, separated by spaces.
+fn print(args: &[Value], _span: Span) -> RuntimeResult<Value> {
+    let line = args
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!("{line}");
+    Ok(Value::Unit)
+}
+
+/// Returns the number of characters in a `String`.
+fn len(args: &[Value], span: Span) -> RuntimeResult<Value> {
+    match args {
+        [Value::String(string)] => Ok(Value::Integer(string.chars().count() as i64)),
+        [value] => Err(RuntimeError::new(
+            format!("`len` expects a String, but found: {}", value.type_name()),
+            span,
+        )),
+        _ => Err(RuntimeError::new(
+            format!("`len` expects 1 argument, but found: {}", args.len()),
+            span,
+        )),
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::Value;
+
+/// Shared, mutable handle to an `Environment`.
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+/// Lexical scope mapping variable names to their values.
+///
+/// Each scope may have a parent; lookups and assignments that fail in the
+/// current scope are retried in the enclosing scopes.
+#[derive(Debug, Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    parent: Option<EnvRef>,
+}
+
+impl Environment {
+    /// Constructs a new, empty environment without a parent.
+    pub fn new() -> EnvRef {
+        Rc::new(RefCell::new(Self::default()))
+    }
+
+    /// Constructs a new, empty environment enclosed by `parent`.
+    pub fn with_parent(parent: EnvRef) -> EnvRef {
+        Rc::new(RefCell::new(Self {
+            values: HashMap::new(),
+            parent: Some(parent),
+        }))
+    }
+
+    /// Binds `name` in this scope, shadowing any existing binding.
+    pub fn define(&mut self, name: impl Into<String>, value: Value) {
+        self.values.insert(name.into(), value);
+    }
+
+    /// Looks up the value bound to `name` in this or an enclosing scope.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self.values.get(name) {
+            Some(value) => Some(value.clone()),
+            None => self.parent.as_ref()?.borrow().get(name),
+        }
+    }
+
+    /// Updates the nearest existing binding of `name`.
+    ///
+    /// Returns `false` if `name` is not bound in any enclosing scope.
+    pub fn assign(&mut self, name: &str, value: Value) -> bool {
+        if let Some(slot) = self.values.get_mut(name) {
+            *slot = value;
+            return true;
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign(name, value),
+            None => false,
+        }
+    }
+}
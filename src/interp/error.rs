@@ -0,0 +1,45 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::lexer::Span;
+
+/// Convenience alias for results produced by the interpreter.
+pub type RuntimeResult<T> = Result<T, RuntimeError>;
+
+/// Error produced while evaluating a program, such as a type mismatch or a
+/// reference to an undefined variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuntimeError {
+    /// Description of the error.
+    message: String,
+    /// The span of the node that failed to evaluate.
+    span: Span,
+}
+
+impl RuntimeError {
+    /// Constructs a `RuntimeError` from a message and the span it refers to.
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the `Span` of the node that failed to evaluate.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for RuntimeError {}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::rc::Rc;
+
+use super::environment::EnvRef;
+use super::RuntimeResult;
+use crate::lexer::Span;
+use crate::parser::ast;
+
+/// Values produced when evaluating a program.
+#[derive(Clone, Debug)]
+pub enum Value {
+    /// Result of statements and calls that produce no meaningful value.
+    Unit,
+    Bool(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Char(char),
+    /// User-defined function, along with the environment it was declared in.
+    Function(Rc<Function>),
+    /// Function implemented natively by the interpreter.
+    Builtin(Builtin),
+}
+
+/// User-defined function, closing over the scope of its declaration.
+pub struct Function {
+    pub decl: ast::Function,
+    pub env: EnvRef,
+}
+
+/// Function implemented natively by the interpreter, such as `print`.
+#[derive(Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub call: fn(&[Value], Span) -> RuntimeResult<Value>,
+}
+
+impl Value {
+    /// Returns the name of the value's type, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Unit => "Unit",
+            Value::Bool(_) => "Bool",
+            Value::Integer(_) => "Integer",
+            Value::Double(_) => "Double",
+            Value::String(_) => "String",
+            Value::Char(_) => "Char",
+            Value::Function(_) | Value::Builtin(_) => "Function",
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Double(a), Value::Double(b)) => a == b,
+            (Value::Integer(a), Value::Double(b)) | (Value::Double(b), Value::Integer(a)) => {
+                *a as f64 == *b
+            }
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::Builtin(a), Value::Builtin(b)) => a.name == b.name,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Integer(value) => write!(f, "{value}"),
+            Value::Double(value) => write!(f, "{value}"),
+            Value::String(value) => write!(f, "{value}"),
+            Value::Char(value) => write!(f, "{value}"),
+            Value::Function(function) => write!(f, "<fn {}>", function.decl.name),
+            Value::Builtin(builtin) => write!(f, "<builtin {}>", builtin.name),
+        }
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<fn {}>", self.decl.name)
+    }
+}
+
+impl fmt::Debug for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<builtin {}>", self.name)
+    }
+}
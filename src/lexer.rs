@@ -1,10 +1,16 @@
 //! This module contains the lexer, which tokenizes the input source.
 
-use rules::{definitions, unambiguous_single_char, Rule};
+pub use error::{LexError, LexErrorKind};
+use rules::{
+    definitions, is_valid_escape, is_valid_unicode_escape, match_string_continuation,
+    raw_string_opener, unambiguous_single_char, Rule,
+};
+pub(crate) use rules::{suffix_start, TRIPLE_QUOTE};
 pub use token::{Kind, Span, Token};
 
 use crate::tk;
 
+mod error;
 pub mod macros;
 mod rules;
 mod token;
@@ -15,6 +21,10 @@ pub struct Lexer<'input> {
     position: usize,
     eof: bool,
     rules: Vec<Rule>,
+    errors: Vec<LexError>,
+    /// Brace depth within each expression embedded in an interpolated
+    /// string, innermost last.
+    interpolations: Vec<usize>,
 }
 
 impl<'input> Lexer<'input> {
@@ -24,9 +34,16 @@ impl<'input> Lexer<'input> {
             position: 0,
             eof: false,
             rules: definitions(),
+            errors: Vec::new(),
+            interpolations: Vec::new(),
         }
     }
 
+    /// Returns the reasons for the error tokens produced so far.
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
+
     /// Iterates over input, collecting tokens into a `Vec`.
     pub fn tokenize(&mut self) -> Vec<Token> {
         self.collect()
@@ -34,10 +51,41 @@ impl<'input> Lexer<'input> {
 
     /// Attempts to consume the next token, emitting an error on failure.
     pub fn next_token(&mut self, input: &str) -> Token {
-        match self.validate(input) {
+        // A `}` closing an embedded expression resumes the interpolated string.
+        if self.interpolations.last() == Some(&0) && input.starts_with('}') {
+            self.interpolations.pop();
+            return self.string_continuation(input);
+        }
+
+        let token = match self.validate(input) {
             Some(token) => token,
             None => self.handle_error(input),
+        };
+        match (token.kind(), self.interpolations.last_mut()) {
+            (tk![string_start], _) => self.interpolations.push(0),
+            (tk!['{'], Some(depth)) => *depth += 1,
+            (tk!['}'], Some(depth)) => *depth -= 1,
+            _ => {}
         }
+        token
+    }
+
+    /// Lexes the fragment of an interpolated string following an embedded
+    /// expression, up to the next embedded expression or the closing quote.
+    fn string_continuation(&mut self, input: &str) -> Token {
+        let start = self.position;
+        let (len, kind) = match match_string_continuation(input) {
+            Some((len, kind)) => {
+                if kind == tk![string_middle] {
+                    self.interpolations.push(0);
+                }
+                (len, kind)
+            }
+            None => (self.string_error(input), tk![error]),
+        };
+
+        self.position = start + len;
+        Token::new(kind, Span::new(start, start + len))
     }
 
     /// Validates the next token from the input source.
@@ -68,6 +116,21 @@ impl<'input> Lexer<'input> {
                 .max_by_key(|&(len, _)| len)?
         };
 
+        // Numbers running straight into other characters, as in `123abc`, are
+        // rejected as a whole rather than split into separate tokens.
+        if matches!(kind, tk![integer] | tk![double]) && input[len..].starts_with(is_word_char) {
+            return None;
+        }
+        // Likewise, an unclosed block comment is not a `/` followed by a `*`,
+        // and unclosed raw or triple-quoted strings are not an `r` or `""`
+        // followed by the rest.
+        if (kind != tk![comment] && input.starts_with("/*"))
+            || (kind == tk![identifier] && raw_string_opener(input).is_some())
+            || (input.starts_with(TRIPLE_QUOTE) && len < 2 * TRIPLE_QUOTE.len())
+        {
+            return None;
+        }
+
         // Set the span indices
         let start = self.position;
         self.position += len;
@@ -75,21 +138,205 @@ impl<'input> Lexer<'input> {
         Some(Token::new(kind, Span::new(start, start + len)))
     }
 
-    /// Creates an error `Token` when the `next_token` method fails.
+    /// Creates an error `Token` when the `next_token` method fails, recording
+    /// the reason for the error.
     fn handle_error(&mut self, input: &str) -> Token {
         let start = self.position;
+        let next = input.chars().next().unwrap_or_default();
 
-        let len = input
-            .char_indices()
-            .find(|(pos, _)| self.validate(&input[*pos..]).is_some())
-            .map(|(pos, _)| pos)
-            .unwrap_or_else(|| input.len());
+        let len = if next == '"' {
+            self.string_error(input)
+        } else if next == '\'' {
+            self.char_error(input)
+        } else if input.starts_with("/*") {
+            // Block comments only fail to match when they are never closed,
+            // so the rest of the input belongs to the comment.
+            self.error(LexErrorKind::UnterminatedComment, 0, 2);
+            input.len()
+        } else if let Some(opener) = raw_string_opener(input) {
+            // Likewise for raw strings, which contain no escape sequences.
+            self.error(LexErrorKind::UnterminatedString, 0, opener);
+            input.len()
+        } else if next.is_ascii_digit()
+            || (next == '.' && input[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            self.number_error(input)
+        } else {
+            let len = input
+                .char_indices()
+                .skip(1)
+                .find(|(pos, _)| self.validate(&input[*pos..]).is_some())
+                .map(|(pos, _)| pos)
+                .unwrap_or_else(|| input.len());
+            self.position = start;
+            self.error(LexErrorKind::UnexpectedCharacter(next), 0, next.len_utf8());
+            len
+        };
         debug_assert!(len <= input.len());
 
-        let len = len;
         self.position = start + len;
         Token::new(tk![error], Span::new(start, start + len))
     }
+
+    /// Finds the extent of a regular or triple-quoted string literal that
+    /// failed to match, recording any invalid escape sequences or a missing
+    /// closing quote.
+    ///
+    /// The fragments of an interpolated string end at the start of the next
+    /// embedded expression instead, if there is one.
+    fn string_error(&mut self, input: &str) -> usize {
+        let quote = if input.starts_with(TRIPLE_QUOTE) {
+            TRIPLE_QUOTE
+        } else {
+            "\""
+        };
+        let mut chars = input.char_indices().skip(quote.len()).peekable();
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '"' if input[pos..].starts_with(quote) => return pos + quote.len(),
+                '{' if quote != TRIPLE_QUOTE => {
+                    self.interpolations.push(0);
+                    return pos + 1;
+                }
+                '\\' => match chars.next() {
+                    Some((end, escape)) => {
+                        self.check_escape(input, pos, end, escape);
+                        // The braces of a `\u{...}` escape never start an
+                        // embedded expression, even if the escape is invalid.
+                        if escape == 'u' && input[end + 1..].starts_with('{') {
+                            while let Some((_, c)) =
+                                chars.next_if(|&(_, c)| !matches!(c, '"' | '\n'))
+                            {
+                                if c == '}' {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    None => break,
+                },
+                _ => {}
+            }
+        }
+
+        // Without a closing quote, the rest of the input belongs to the string.
+        self.error(LexErrorKind::UnterminatedString, 0, quote.len());
+        input.len()
+    }
+
+    /// Finds the extent of a character literal that failed to match,
+    /// recording why it is invalid.
+    fn char_error(&mut self, input: &str) -> usize {
+        // Character literals cannot span multiple lines.
+        let line = input.find('\n').map_or(input, |end| &input[..end]);
+        let mut chars = line.char_indices().skip(1);
+        let mut count = 0;
+        let mut valid = true;
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '\'' => {
+                    // Invalid escapes have already been reported.
+                    if valid && count != 1 {
+                        self.error(LexErrorKind::InvalidChar, 0, pos + 1);
+                    }
+                    return pos + 1;
+                }
+                '\\' => match chars.next() {
+                    Some((end, escape)) => {
+                        valid &= self.check_escape(line, pos, end, escape);
+                        count += 1;
+                    }
+                    None => break,
+                },
+                _ => count += 1,
+            }
+        }
+
+        self.error(LexErrorKind::UnterminatedChar, 0, 1);
+        line.len()
+    }
+
+    /// Checks the escape sequence made of the `\` at `start` and the `escape`
+    /// character at `pos`, recording an error if it is invalid.
+    fn check_escape(&mut self, input: &str, start: usize, pos: usize, escape: char) -> bool {
+        if is_valid_escape(escape) {
+            return true;
+        }
+        if escape != 'u' {
+            let end = pos + escape.len_utf8();
+            self.error(LexErrorKind::InvalidEscape(escape), start, end);
+            return false;
+        }
+
+        let rest = &input[pos + 1..];
+        if is_valid_unicode_escape(rest) {
+            return true;
+        }
+        // Point at the braces too, if they are closed.
+        let braces = rest
+            .strip_prefix('{')
+            .and_then(|inner| {
+                let end = inner.find(['}', '"', '\'', '\n'])?;
+                inner[end..].starts_with('}').then_some(end)
+            })
+            .map_or(0, |end| end + 2);
+        self.error(LexErrorKind::InvalidUnicodeEscape, start, pos + 1 + braces);
+        false
+    }
+
+    /// Finds the extent of a number literal running into other characters,
+    /// as in `123abc`, `1.5x2` or `10u7`.
+    fn number_error(&mut self, input: &str) -> usize {
+        let len = input
+            .char_indices()
+            .find(|&(pos, c)| {
+                let fraction =
+                    c == '.' && input[pos + 1..].starts_with(|c: char| c.is_ascii_digit());
+                !(is_word_char(c) || fraction)
+            })
+            .map_or(input.len(), |(pos, _)| pos);
+
+        // An exponent missing its digits, as in `1e` or `2.5E-`, is reported
+        // on its own.
+        let number = &input[..len];
+        if let Some(e) = number.find(['e', 'E']).filter(|&e| e + 1 == len) {
+            if number[..e]
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '_' || c == '.')
+            {
+                let sign = usize::from(input[len..].starts_with(['+', '-']));
+                self.error(LexErrorKind::MissingExponent, e, len + sign);
+                return len + sign;
+            }
+        }
+
+        // A valid number followed by an unknown type suffix, as in `10u7`, is
+        // also reported on its own.
+        let is_number = |text: &str| {
+            self.rules.iter().any(|rule| {
+                matches!(rule.kind, tk![integer] | tk![double])
+                    && (rule.matches)(text) == Some(text.len())
+            })
+        };
+        if let Some(suffix) = suffix_start(number).filter(|&pos| is_number(&number[..pos])) {
+            self.error(LexErrorKind::InvalidSuffix, suffix, len);
+            return len;
+        }
+
+        self.error(LexErrorKind::MalformedNumber, 0, len);
+        len
+    }
+
+    /// Records an error spanning `start..end`, relative to the current position.
+    fn error(&mut self, kind: LexErrorKind, start: usize, end: usize) {
+        let span = Span::new(self.position + start, self.position + end);
+        self.errors.push(LexError::new(kind, span));
+    }
+}
+
+/// Checks whether a character may continue an identifier or number.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
 }
 
 impl<'input> Iterator for Lexer<'input> {
@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::fmt;
+
+use super::Span;
+
+/// The reason the lexer produced an error token.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LexErrorKind {
+    /// A string literal is missing its closing quote.
+    UnterminatedString,
+    /// A character literal is missing its closing quote.
+    UnterminatedChar,
+    /// A character literal does not contain exactly one character.
+    InvalidChar,
+    /// A block comment is missing its closing `*/`.
+    UnterminatedComment,
+    /// A string literal contains an unknown escape sequence, such as `\q`.
+    InvalidEscape(char),
+    /// A `\u` escape is not followed by 1 to 6 hex digits in braces.
+    InvalidUnicodeEscape,
+    /// A number literal is immediately followed by other characters.
+    MalformedNumber,
+    /// The exponent of a number literal has no digits, as in `1e` or `2e+`.
+    MissingExponent,
+    /// A number literal has an unknown type suffix, as in `10u7`.
+    InvalidSuffix,
+    /// A character does not start any valid token.
+    UnexpectedCharacter(char),
+}
+
+/// Error recorded by the lexer, explaining why a `Kind::Error` token was
+/// produced.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LexError {
+    kind: LexErrorKind,
+    /// The span pointing at the cause of the error, which may be narrower
+    /// than the error token itself.
+    span: Span,
+}
+
+impl LexError {
+    /// Constructs a `LexError` from its reason and the span it refers to.
+    pub fn new(kind: LexErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Returns the reason for the error.
+    pub fn kind(&self) -> LexErrorKind {
+        self.kind
+    }
+
+    /// Returns the `Span` pointing at the cause of the error.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LexErrorKind::UnterminatedString => write!(f, "Unterminated string literal"),
+            LexErrorKind::UnterminatedChar => write!(f, "Unterminated character literal"),
+            LexErrorKind::InvalidChar => {
+                write!(f, "Character literals must contain exactly one character")
+            }
+            LexErrorKind::UnterminatedComment => write!(f, "Unterminated block comment"),
+            LexErrorKind::InvalidEscape(c) => {
+                write!(f, "Invalid escape sequence: `\\{}`", c.escape_default())
+            }
+            LexErrorKind::InvalidUnicodeEscape => write!(f, "Invalid unicode escape sequence"),
+            LexErrorKind::MalformedNumber => write!(f, "Malformed number literal"),
+            LexErrorKind::MissingExponent => write!(f, "Missing digits in exponent"),
+            LexErrorKind::InvalidSuffix => write!(f, "Invalid suffix for number literal"),
+            LexErrorKind::UnexpectedCharacter(c) => {
+                write!(f, "Unexpected character: `{}`", c.escape_default())
+            }
+        }
+    }
+}
+
+impl Error for LexError {}
@@ -73,9 +73,24 @@ macro_rules! tk {
     [string] => {
         $crate::lexer::Kind::String
     };
+    [char] => {
+        $crate::lexer::Kind::Char
+    };
+    [string_start] => {
+        $crate::lexer::Kind::StringStart
+    };
+    [string_middle] => {
+        $crate::lexer::Kind::StringMiddle
+    };
+    [string_end] => {
+        $crate::lexer::Kind::StringEnd
+    };
     [comment] => {
         $crate::lexer::Kind::Comment
     };
+    [doc] => {
+        $crate::lexer::Kind::DocComment
+    };
     [integer] => {
         $crate::lexer::Kind::Integer
     };
@@ -120,6 +135,9 @@ macro_rules! tk {
     [<=] => {
         $crate::lexer::Kind::Leq
     };
+    [->] => {
+        $crate::lexer::Kind::Arrow
+    };
     // Whitespace
     [ws] => {
         $crate::lexer::Kind::Whitespace
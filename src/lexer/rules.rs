@@ -8,15 +8,95 @@ use crate::tk;
 
 lazy_static! {
     static ref COMMENT_REGEX: Regex =
-        Regex::new(r#"^//[^\n]*\n"#).expect("Comment regex must be valid.");
+        Regex::new(r#"^//[^\n]*(\n|$)"#).expect("Comment regex must be valid.");
+    // `///` and `//!` start doc comments, but `////` is a regular comment.
+    static ref DOC_COMMENT_REGEX: Regex =
+        Regex::new(r#"^//(![^\n]*|/|/[^/\n][^\n]*)(\n|$)"#).expect("Doc comment regex must be valid.");
     static ref STRING_REGEX: Regex =
-        Regex::new(r#"^"((\\"|\\\\)|[^\\"])*""#).expect("String regex must be valid.");
-    static ref DOUBLE_REGEX: Regex =
-        Regex::new(r#"^((\d+\.\d+)|(\.\d+))([eE][-+]?\d+)?"#).expect("Double regex must be valid.");
-    static ref INTEGER_REGEX: Regex =
-        Regex::new(r#"^(0|\d+)"#).expect("Integer regex must be valid.");
-    static ref IDENTIFIER_REGEX: Regex =
-        Regex::new(r#"^([a-zA-Z]|_)([a-zA-Z]|_|\d)*"#).expect("Identifier regex must be valid.");
+        Regex::new(&format!(r#"^"{STRING_CHAR}*""#)).expect("String regex must be valid.");
+    // Interpolated strings are split into fragments at each embedded
+    // expression, as in `"a {`, `} b {` and `} c"`.
+    static ref STRING_START_REGEX: Regex =
+        Regex::new(&format!(r#"^"{STRING_CHAR}*\{{"#)).expect("String start regex must be valid.");
+    static ref STRING_MIDDLE_REGEX: Regex =
+        Regex::new(&format!(r#"^\}}{STRING_CHAR}*\{{"#)).expect("String middle regex must be valid.");
+    static ref STRING_END_REGEX: Regex =
+        Regex::new(&format!(r#"^\}}{STRING_CHAR}*""#)).expect("String end regex must be valid.");
+    static ref CHAR_REGEX: Regex =
+        Regex::new(r#"^'(\\['"\\nrt0{}]|\\u\{[0-9a-fA-F]{1,6}\}|[^\\'\n])'"#)
+            .expect("Char regex must be valid.");
+    // Doubles have a fractional part, an exponent, a double suffix or any of
+    // these, as in `1.5`, `.5`, `1e10`, `2.5E-3` or `1f32`. Digits may be
+    // separated by underscores, as in `1_000.5`, but must start with a digit.
+    static ref DOUBLE_REGEX: Regex = Regex::new(&format!(
+        r#"^(((\d[\d_]*\.\d[\d_]*|\.\d[\d_]*)([eE][-+]?_*\d[\d_]*)?|\d[\d_]*[eE][-+]?_*\d[\d_]*){DOUBLE_SUFFIX}?|\d[\d_]*{DOUBLE_SUFFIX})"#
+    ))
+    .expect("Double regex must be valid.");
+    static ref TRAILING_DOT_DOUBLE_REGEX: Regex =
+        Regex::new(r#"^\d[\d_]*\."#).expect("Trailing dot double regex must be valid.");
+    // Integers are decimal, or hex, octal or binary with a `0x`, `0o` or `0b`
+    // prefix, as in `0xFF_FF`, optionally followed by an integer suffix.
+    static ref INTEGER_REGEX: Regex = Regex::new(&format!(
+        r#"^(0x_*[0-9a-fA-F][0-9a-fA-F_]*|0o_*[0-7][0-7_]*|0b_*[01][01_]*|\d[\d_]*){INTEGER_SUFFIX}?"#
+    ))
+    .expect("Integer regex must be valid.");
+    // Identifiers follow UAX #31, so they may contain non-ASCII letters, as
+    // in `größe` or `変数`.
+    static ref IDENTIFIER_REGEX: Regex = Regex::new(r#"^[\p{XID_Start}_]\p{XID_Continue}*"#)
+        .expect("Identifier regex must be valid.");
+}
+
+/// Pattern for a single character or escape sequence in a string literal.
+const STRING_CHAR: &str = r#"(\\["'\\nrt0{}]|\\u\{[0-9a-fA-F]{1,6}\}|[^\\"{])"#;
+
+/// Pattern for the type suffix of an integer literal, as in `10u8`.
+const INTEGER_SUFFIX: &str = r#"([iu](8|16|32|64))"#;
+
+/// Pattern for the type suffix of a double literal, as in `3.5f32`.
+const DOUBLE_SUFFIX: &str = r#"(f(32|64))"#;
+
+/// Delimiter of multi-line string literals.
+pub(crate) const TRIPLE_QUOTE: &str = r#"""""#;
+
+/// Checks whether `\` followed by `c` is a valid escape in a string literal.
+///
+/// Unicode escapes, `\u{...}`, are checked by `is_valid_unicode_escape`.
+pub(crate) fn is_valid_escape(c: char) -> bool {
+    matches!(c, '"' | '\'' | '\\' | 'n' | 'r' | 't' | '0' | '{' | '}')
+}
+
+/// Checks whether `input`, following a `\u`, starts with the braced hex
+/// digits of a unicode escape, as in `{1F600}`.
+pub(crate) fn is_valid_unicode_escape(input: &str) -> bool {
+    let Some(rest) = input.strip_prefix('{') else {
+        return false;
+    };
+    let digits = rest.chars().take_while(char::is_ascii_hexdigit).count();
+    (1..=6).contains(&digits) && rest[digits..].starts_with('}')
+}
+
+/// Returns the position of the type suffix of a number literal, if it has
+/// one, as in the `u8` of `10u8`.
+///
+/// Suffixes start with `i`, `u` or `f`, though `f` is a digit in hex literals.
+pub(crate) fn suffix_start(number: &str) -> Option<usize> {
+    let (prefix, suffixes) = match number.get(..2) {
+        Some("0x") => (2, &['i', 'u'][..]),
+        Some("0o" | "0b") => (2, &['i', 'u', 'f'][..]),
+        _ => (0, &['i', 'u', 'f'][..]),
+    };
+    number[prefix..].find(suffixes).map(|pos| prefix + pos)
+}
+
+/// Matches the continuation of an interpolated string after an embedded
+/// expression, which starts at its closing `}`.
+///
+/// Returns `tk![string_middle]` if another expression follows, or
+/// `tk![string_end]` if the string is closed.
+pub(crate) fn match_string_continuation(input: &str) -> Option<(usize, Kind)> {
+    match_regex(input, &STRING_MIDDLE_REGEX)
+        .map(|len| (len, tk![string_middle]))
+        .or_else(|| Some((match_regex(input, &STRING_END_REGEX)?, tk![string_end])))
 }
 
 /// Defines a single rule for the lexer.
@@ -45,7 +125,7 @@ fn match_multi_char(input: &str, first: char, second: char) -> Option<usize> {
 
 /// Match against keyword token kinds, returning `None` on failure.
 fn match_keyword(input: &str, keyword: &str) -> Option<usize> {
-    input.starts_with(keyword).then(|| keyword.len())
+    input.starts_with(keyword).then_some(keyword.len())
 }
 
 /// Match against regular expression, returning `None` on failure.
@@ -53,6 +133,83 @@ fn match_regex(input: &str, re: &Regex) -> Option<usize> {
     re.find(input).map(|m| m.end())
 }
 
+/// Returns the length of the opening `r"`, `r#"`, `r##"`, etc. of a raw
+/// string literal.
+pub(crate) fn raw_string_opener(input: &str) -> Option<usize> {
+    let hashes = input
+        .strip_prefix('r')?
+        .bytes()
+        .take_while(|&b| b == b'#')
+        .count();
+    input[1 + hashes..].starts_with('"').then_some(hashes + 2)
+}
+
+/// Match against a raw string literal, which ends at a `"` followed by as
+/// many `#`s as it was opened with.
+fn match_raw_string(input: &str) -> Option<usize> {
+    let opener = raw_string_opener(input)?;
+    let closer = format!("\"{}", &input[1..opener - 1]);
+    let end = input[opener..].find(&closer)?;
+    Some(opener + end + closer.len())
+}
+
+/// Match against a triple-quoted string literal, which may contain the same
+/// escapes as a regular string literal.
+fn match_triple_quoted_string(input: &str) -> Option<usize> {
+    let mut chars = input.strip_prefix(TRIPLE_QUOTE)?.char_indices();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '"' if input[TRIPLE_QUOTE.len() + pos..].starts_with(TRIPLE_QUOTE) => {
+                return Some(2 * TRIPLE_QUOTE.len() + pos);
+            }
+            '\\' => match chars.next()? {
+                (_, escape) if is_valid_escape(escape) => {}
+                (end, 'u') if is_valid_unicode_escape(&input[TRIPLE_QUOTE.len() + end + 1..]) => {}
+                _ => return None,
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Match against a double with a trailing dot, as in `5.`.
+///
+/// The dot is not part of the double when followed by another dot or by an
+/// identifier, as in `5..` or `5.max`.
+fn match_trailing_dot_double(input: &str) -> Option<usize> {
+    let len = match_regex(input, &TRAILING_DOT_DOUBLE_REGEX)?;
+    let next = input[len..].chars().next();
+    (!next.is_some_and(|c| c == '.' || c == '_' || c.is_alphanumeric())).then_some(len)
+}
+
+/// Match against a block comment, which may contain nested block comments,
+/// returning `None` if it is never closed.
+fn match_block_comment(input: &str) -> Option<usize> {
+    if !input.starts_with("/*") {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut pos = 0;
+    while pos < input.len() {
+        let rest = &input[pos..];
+        if rest.starts_with("/*") {
+            depth += 1;
+            pos += 2;
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            pos += 2;
+            if depth == 0 {
+                return Some(pos);
+            }
+        } else {
+            pos += rest.chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
 /// Provides the rules to the lexical scanner.
 pub(crate) fn definitions() -> Vec<Rule> {
     vec![
@@ -65,6 +222,14 @@ pub(crate) fn definitions() -> Vec<Rule> {
             kind: tk![=],
             matches: |input| match_single_char(input, '='),
         },
+        Rule {
+            kind: tk![-],
+            matches: |input| match_single_char(input, '-'),
+        },
+        Rule {
+            kind: tk![.],
+            matches: |input| match_single_char(input, '.'),
+        },
         Rule {
             kind: tk![/],
             matches: |input| match_single_char(input, '/'),
@@ -106,6 +271,10 @@ pub(crate) fn definitions() -> Vec<Rule> {
             kind: tk![>=],
             matches: |input| match_multi_char(input, '>', '='),
         },
+        Rule {
+            kind: tk![->],
+            matches: |input| match_multi_char(input, '-', '>'),
+        },
         // Keywords
         Rule {
             kind: tk![let],
@@ -132,10 +301,36 @@ pub(crate) fn definitions() -> Vec<Rule> {
             kind: tk![string],
             matches: |input| match_regex(input, &STRING_REGEX),
         },
+        Rule {
+            kind: tk![string_start],
+            matches: |input| match_regex(input, &STRING_START_REGEX),
+        },
+        Rule {
+            kind: tk![string],
+            matches: match_raw_string,
+        },
+        Rule {
+            kind: tk![string],
+            matches: match_triple_quoted_string,
+        },
+        // Doc comments also match as regular comments, so they must come
+        // first to win the tie.
+        Rule {
+            kind: tk![doc],
+            matches: |input| match_regex(input, &DOC_COMMENT_REGEX),
+        },
         Rule {
             kind: tk![comment],
             matches: |input| match_regex(input, &COMMENT_REGEX),
         },
+        Rule {
+            kind: tk![comment],
+            matches: match_block_comment,
+        },
+        Rule {
+            kind: tk![char],
+            matches: |input| match_regex(input, &CHAR_REGEX),
+        },
         Rule {
             kind: tk![integer],
             matches: |input| match_regex(input, &INTEGER_REGEX),
@@ -144,6 +339,10 @@ pub(crate) fn definitions() -> Vec<Rule> {
             kind: tk![double],
             matches: |input| match_regex(input, &DOUBLE_REGEX),
         },
+        Rule {
+            kind: tk![double],
+            matches: match_trailing_dot_double,
+        },
         Rule {
             kind: tk![identifier],
             matches: |input| match_regex(input, &IDENTIFIER_REGEX),
@@ -157,10 +356,8 @@ pub(crate) fn definitions() -> Vec<Rule> {
 pub(crate) const fn unambiguous_single_char(c: char) -> Option<Kind> {
     Some(match c {
         '+' => tk![+],
-        '-' => tk![-],
         '*' => tk![*],
         '^' => tk![^],
-        '.' => tk![.],
         ',' => tk![,],
         ':' => tk![:],
         ';' => tk![;],
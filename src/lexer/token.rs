@@ -31,7 +31,12 @@ pub enum Kind {
     RParen,
     // Multi-character
     String,
+    StringStart,
+    Char,
+    StringMiddle,
+    StringEnd,
     Comment,
+    DocComment,
     Integer,
     Double,
     Identifier,
@@ -48,6 +53,7 @@ pub enum Kind {
     Neq,
     Geq,
     Leq,
+    Arrow,
     // Whitespace
     Whitespace,
     // End of file
@@ -88,7 +94,12 @@ impl fmt::Display for Kind {
                 tk!['}'] => "}",
                 // Multi-character
                 tk![string] => "String",
+                tk![char] => "Char",
+                tk![string_start] => "\"...{",
+                tk![string_middle] => "}...{",
+                tk![string_end] => "}...\"",
                 tk![comment] => "// Comment",
+                tk![doc] => "/// Doc comment",
                 tk![integer] => "Integer",
                 tk![double] => "Double",
                 tk![identifier] => "Identifier",
@@ -105,6 +116,7 @@ impl fmt::Display for Kind {
                 tk![!=] => "!=",
                 tk![>=] => ">=",
                 tk![<=] => "<=",
+                tk![->] => "->",
                 // Whitespace
                 tk![ws] => "<WS>",
                 // End of file
@@ -153,6 +165,11 @@ impl Span {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the smallest span covering both `self` and `other`.
+    pub fn merge(&self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
 }
 
 impl From<Range<usize>> for Span {
@@ -259,6 +276,7 @@ mod tests {
         assert_eq!(tk![let].to_string(), "let");
         assert_eq!(tk![error].to_string(), "<?>");
         assert_eq!(tk![comment].to_string(), "// Comment");
+        assert_eq!(tk![doc].to_string(), "/// Doc comment");
     }
 
     #[test]
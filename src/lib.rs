@@ -1,7 +1,12 @@
 //! Transient is an experimental, domain-specific language implementation.
 //!
 //! The project contains a lexical scanner, or lexer, to tokenize input along
-//! with a parser to assemble an abstract syntax tree (AST).
+//! with a parser to assemble an abstract syntax tree (AST) and an interpreter
+//! to evaluate it.
 
+pub mod diagnostic;
+pub mod interp;
 pub mod lexer;
 pub mod parser;
+pub mod repl;
+pub mod source;
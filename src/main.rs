@@ -0,0 +1,196 @@
+//! Command-line interface for lexing, parsing and running transient programs.
+
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::process::ExitCode;
+use std::{env, fs};
+
+use transient::diagnostic::{Diagnostic, Renderer};
+use transient::interp::{Interpreter, Value};
+use transient::lexer::{LexError, Lexer};
+use transient::parser::{ast, Parser};
+use transient::repl::{Outcome, Repl};
+use transient::tk;
+
+const USAGE: &str = "\
+Usage: transient <COMMAND> [FILE]
+
+Commands:
+  lex    Print the tokens of the input
+  parse  Print the abstract syntax tree of the input
+  run    Execute the input as a program
+  repl   Start an interactive session
+
+Reads from standard input when FILE is omitted or is `-`.";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (command, path) = match args.as_slice() {
+        [command] => (command.as_str(), None),
+        [command, path] => (command.as_str(), Some(path.as_str())),
+        _ => return usage_error(),
+    };
+
+    match command {
+        "-h" | "--help" | "help" => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        "repl" if path.is_none() => return repl(),
+        "lex" | "parse" | "run" => {}
+        _ => return usage_error(),
+    }
+
+    let input = match read_input(path) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("error: failed to read `{}`: {error}", path.unwrap_or("-"));
+            return ExitCode::from(2);
+        }
+    };
+    let source = Source::new(path.filter(|&p| p != "-").unwrap_or("<stdin>"), &input);
+
+    match command {
+        "lex" => lex(&source),
+        "parse" => parse(&source).map(|program| println!("{program:#?}")),
+        "run" => parse(&source).and_then(|program| run(&source, &program)),
+        _ => unreachable!("Commands are validated before reading input."),
+    }
+    .map_or(ExitCode::FAILURE, |()| ExitCode::SUCCESS)
+}
+
+/// Named input source, used to report errors.
+struct Source<'a> {
+    text: &'a str,
+    renderer: Renderer<'a>,
+}
+
+impl<'a> Source<'a> {
+    fn new(name: &'a str, text: &'a str) -> Self {
+        let color = io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+        Self {
+            text,
+            renderer: Renderer::new(name, text).with_color(color),
+        }
+    }
+
+    /// Renders a diagnostic to standard error.
+    fn report(&self, diagnostic: impl Into<Diagnostic>) {
+        eprint!("{}", self.renderer.render(&diagnostic.into()));
+    }
+
+    /// Renders the reasons for any error tokens, failing if there were any.
+    fn report_lex_errors(&self, errors: &[LexError]) -> Result<(), ()> {
+        for error in errors {
+            self.report(error);
+        }
+        errors.is_empty().then_some(()).ok_or(())
+    }
+}
+
+/// Prints the usage message, returning the exit code for invalid arguments.
+fn usage_error() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+/// Reads the program source from the file at `path`, or from standard input.
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path),
+    }
+}
+
+/// Prints every token of the input, failing if any error tokens were found.
+fn lex(source: &Source) -> Result<(), ()> {
+    let mut lexer = Lexer::new(source.text);
+    for token in lexer.by_ref() {
+        println!("{token:?}");
+    }
+    source.report_lex_errors(lexer.errors())
+}
+
+/// Parses the input into a program, reporting any errors encountered.
+fn parse(source: &Source) -> Result<ast::Program, ()> {
+    let mut lexer = Lexer::new(source.text);
+    lexer.by_ref().for_each(drop);
+    let lexed = source.report_lex_errors(lexer.errors());
+
+    let mut parser = Parser::new(source.text);
+    let program = parser.program();
+    // Error tokens have already been reported along with their reason.
+    let errors = parser
+        .errors()
+        .iter()
+        .filter(|error| error.found().kind() != tk![error])
+        .collect::<Vec<_>>();
+    for &error in &errors {
+        source.report(error);
+    }
+
+    lexed?;
+    errors.is_empty().then_some(program).ok_or(())
+}
+
+/// Executes the program, reporting a runtime error if one occurs.
+fn run(source: &Source, program: &ast::Program) -> Result<(), ()> {
+    Interpreter::new()
+        .run(program)
+        .map_err(|error| source.report(&error))
+}
+
+/// Runs an interactive session until the end of standard input.
+fn repl() -> ExitCode {
+    let mut repl = Repl::new();
+    let mut stdin = io::stdin().lock();
+    let mut line = String::new();
+    loop {
+        print!("{}", if repl.is_continuing() { ".. " } else { ">> " });
+        io::stdout().flush().ok();
+
+        line.clear();
+        match stdin.read_line(&mut line) {
+            Ok(0) => {
+                println!();
+                return ExitCode::SUCCESS;
+            }
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("error: failed to read input: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+
+        match repl.feed(line.trim_end_matches(['\r', '\n'])) {
+            Outcome::Incomplete => {}
+            Outcome::Values(values) => {
+                for value in values.iter().filter(|v| **v != Value::Unit) {
+                    match value {
+                        Value::String(string) => {
+                            println!("{}", ast::Literal::String(string.clone()))
+                        }
+                        Value::Char(char) => println!("{}", ast::Literal::Char(*char)),
+                        value => println!("{value}"),
+                    }
+                }
+            }
+            Outcome::LexErrors(errors) => {
+                let source = Source::new("<repl>", repl.source());
+                source.report_lex_errors(&errors).ok();
+            }
+            Outcome::ParseErrors(errors) => {
+                let source = Source::new("<repl>", repl.source());
+                for error in errors {
+                    source.report(&error);
+                }
+            }
+            Outcome::RuntimeError(error) => {
+                Source::new("<repl>", repl.source()).report(&error);
+            }
+        }
+    }
+}
@@ -1,13 +1,28 @@
 //! This module contains transient's core parsing logic.
 use std::iter::Peekable;
 
-use crate::lexer::{Kind, Lexer, Token};
+use unicode_normalization::UnicodeNormalization;
+
+pub use error::{ParseError, ParseResult};
+
+use crate::lexer::{Kind, Lexer, Span, Token};
 use crate::tk;
 
 pub mod ast;
+mod error;
 mod expression;
 mod hierarchy;
+mod item;
+mod literal;
 mod operator;
+mod types;
+
+/// Maximum nesting depth of expressions and statements, beyond which a
+/// `ParseError` is returned rather than overflowing the stack.
+///
+/// Like the interpreter's call depth limit, this leaves room to spare on the
+/// 2 MiB stack of a spawned thread in debug builds.
+const MAX_NESTING: usize = 64;
 
 /// Left-to-right, leftmost derivation parser implementation - LL(1) parser.
 pub struct Parser<'input, I>
@@ -16,6 +31,14 @@ where
 {
     input: &'input str,
     tokens: Peekable<I>,
+    errors: Vec<ParseError>,
+    /// Span of the most recently consumed token.
+    previous: Span,
+    /// Number of expressions and statements currently being parsed.
+    depth: usize,
+    /// Doc comments preceding the next token. They are attached to the
+    /// statement it starts, if any, and dropped otherwise.
+    docs: Vec<Token>,
 }
 
 /// Iterator for producing tokens with whitespace and comments stripped out.
@@ -23,6 +46,9 @@ where
 /// `TokenIter` wraps the `Lexer` and filters out any whitespace or comment
 /// token kinds. This utility type makes it so that we don't need to worry about
 /// either. When we need our next token, we simply call `next` on `TokenIter`.
+///
+/// Doc comments are kept, as the parser attaches them to the AST. The parser
+/// sets them aside as it goes, so they never appear as the next token.
 pub struct TokenIter<'input> {
     lexer: Lexer<'input>,
 }
@@ -53,6 +79,10 @@ impl<'input> Parser<'input, TokenIter<'input>> {
         Self {
             input,
             tokens: TokenIter::new(input).peekable(),
+            errors: Vec::new(),
+            previous: Span::new(0, 0),
+            depth: 0,
+            docs: Vec::new(),
         }
     }
 }
@@ -66,9 +96,40 @@ where
         token.text(self.input)
     }
 
+    /// Gets the name of an identifier token, normalized to NFC so that
+    /// canonically equivalent names, such as a precomposed `é` and an `e`
+    /// followed by a combining accent, compare equal.
+    pub(crate) fn name(&self, token: Token) -> String {
+        self.text(token).nfc().collect()
+    }
+
+    /// Returns the errors the parser has recovered from so far.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    /// Takes the errors the parser has recovered from, leaving none behind.
+    pub fn take_errors(&mut self) -> Vec<ParseError> {
+        std::mem::take(&mut self.errors)
+    }
+
     /// Attempts to look ahead to determine what the next token `Kind` is.
     pub(crate) fn peek(&mut self) -> Kind {
-        self.tokens.peek().map(|t| t.kind()).unwrap_or(tk![EOF])
+        self.peek_token().kind()
+    }
+
+    /// Looks ahead at the next token without consuming it, setting aside any
+    /// doc comments in front of it.
+    ///
+    /// An `EOF` token is returned once the token stream has been exhausted.
+    pub(crate) fn peek_token(&mut self) -> Token {
+        while let Some(doc) = self.tokens.next_if(|token| token.kind() == tk![doc]) {
+            self.docs.push(doc);
+        }
+        match self.tokens.peek() {
+            Some(token) => *token,
+            None => Token::new(tk![EOF], Span::new(self.input.len(), self.input.len())),
+        }
     }
 
     /// Checks whether the next token is a particular `Kind` of token.
@@ -77,22 +138,147 @@ where
     }
 
     /// Gets the next token from the lexer.
+    ///
+    /// Doc comments that were not attached to a statement are dropped.
     pub(crate) fn next(&mut self) -> Option<Token> {
-        self.tokens.next()
+        self.peek_token();
+        self.docs.clear();
+        let token = self.tokens.next()?;
+        self.previous = token.span();
+        Some(token)
+    }
+
+    /// Runs `parse` one level of nesting deeper, failing with `message`
+    /// instead once `MAX_NESTING` levels are reached.
+    pub(crate) fn nested<T>(
+        &mut self,
+        message: &str,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let depth = self.depth;
+        self.descend(message)?;
+        let result = parse(self);
+        self.depth = depth;
+        result
+    }
+
+    /// Goes one level of nesting deeper, failing with `message` instead once
+    /// `MAX_NESTING` levels are reached. The caller restores the depth.
+    pub(crate) fn descend(&mut self, message: &str) -> ParseResult<()> {
+        if self.depth >= MAX_NESTING {
+            return Err(ParseError::new(self.peek_token(), vec![], message));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Returns the span from `start` up to the end of the last consumed token.
+    pub(crate) fn span_from(&self, start: Span) -> Span {
+        start.merge(self.previous)
     }
 
     /// Consumes a single token while verifying it is the expected token kind.
-    pub(crate) fn consume(&mut self, expected: Kind) {
-        let token = self
-            .next()
-            .expect(&*format!("Found `EOF`, but expected: `{}`", expected));
-
-        assert_eq!(
-            token.kind(),
-            expected,
-            "Found `{}`, but expected: `{}`",
-            token.kind(),
-            expected
-        );
+    ///
+    /// The token is left in place if it does not match the expected kind.
+    pub(crate) fn consume(&mut self, expected: Kind) -> ParseResult<Token> {
+        let token = self.peek_token();
+        if token.kind() != expected {
+            return Err(ParseError::new(
+                token,
+                vec![expected],
+                format!("Found `{}`, but expected: `{}`", token.kind(), expected),
+            ));
+        }
+
+        self.next();
+        Ok(token)
+    }
+
+    /// Consumes an identifier token expected to follow the `after` token.
+    pub(crate) fn expect_identifier(&mut self, after: Kind) -> ParseResult<Token> {
+        let token = self.peek_token();
+        if token.kind() != tk![identifier] {
+            return Err(ParseError::new(
+                token,
+                vec![tk![identifier]],
+                format!(
+                    "Expected identifier after `{after}`, but found: `{}`",
+                    token.kind()
+                ),
+            ));
+        }
+
+        self.next();
+        Ok(token)
+    }
+
+    /// Takes the doc comments preceding the next token, returning their lines.
+    ///
+    /// Only outer (`///`) doc comments are taken, unless `inner` is set, in
+    /// which case only inner (`//!`) doc comments are.
+    pub(crate) fn doc_comments(&mut self, inner: bool) -> Vec<String> {
+        let marker = if inner { "//!" } else { "///" };
+        self.peek_token();
+        let (docs, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.docs)
+            .into_iter()
+            .partition(|&token| self.text(token).starts_with(marker));
+        self.docs = rest;
+
+        docs.into_iter()
+            .map(|token| {
+                let line = self.text(token)[marker.len()..].trim_end_matches(['\n', '\r']);
+                line.strip_prefix(' ').unwrap_or(line).to_string()
+            })
+            .collect()
+    }
+
+    /// Parses statements until the `terminator` token, recovering from errors.
+    ///
+    /// Whenever a statement fails to parse, its error is recorded and the
+    /// parser skips ahead to the next synchronization point before carrying
+    /// on. The `terminator` itself is left unconsumed.
+    pub(crate) fn statements_until(&mut self, terminator: Kind) -> Vec<ast::Stmt> {
+        let mut stmts = vec![];
+        while !self.at(terminator) && !self.at(tk![EOF]) {
+            let start = self.peek_token();
+            match self.statement() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(error) => {
+                    self.errors.push(error);
+                    // Always make progress, even if the statement failed on
+                    // its very first token. A `{` is left for `synchronize`
+                    // to skip along with the rest of its block.
+                    if self.peek_token() == start && !self.at(tk!['{']) {
+                        self.next();
+                    }
+                    self.synchronize();
+                }
+            }
+        }
+        stmts
+    }
+
+    /// Skips tokens until reaching a point where parsing can safely resume.
+    ///
+    /// Synchronization points are the end of a statement (`;`, which is
+    /// consumed), the end of the enclosing block (`}`) and any keyword that
+    /// starts a statement. Nested blocks are skipped over as a whole.
+    pub(crate) fn synchronize(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                tk![EOF] => return,
+                tk![;] if depth == 0 => {
+                    self.next();
+                    return;
+                }
+                tk!['}'] if depth == 0 => return,
+                tk![let] | tk![if] | tk![fn] | tk![struct] if depth == 0 => return,
+                tk!['{'] => depth += 1,
+                tk!['}'] => depth -= 1,
+                _ => {}
+            }
+            self.next();
+        }
     }
 }
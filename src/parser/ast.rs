@@ -1,11 +1,94 @@
 use std::fmt;
+use std::ops::RangeInclusive;
 
-use crate::lexer::Kind;
+use crate::lexer::{Kind, Span};
 
+/// Root of the syntax tree, representing a complete source file.
 #[derive(Clone, Debug, PartialEq)]
-pub enum Stmt {
+pub struct Program {
+    /// Lines of the `//!` doc comments at the start of the program.
+    pub docs: Vec<String>,
+    /// Top-level statements, in source order.
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
+/// Declarations that introduce a named item into the program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+    /// Function declaration: `fn name(param: Type, ...) -> Type { ... }`
+    Function(Function),
+    /// Struct declaration: `struct Name { field: Type, ... }`
+    Struct(Struct),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function {
+    /// Lines of the `///` doc comments preceding the declaration.
+    pub docs: Vec<String>,
+    pub name: String,
+    pub params: Vec<Param>,
+    /// The declared return type, if any.
+    pub ret: Option<Type>,
+    pub body: Vec<Stmt>,
+}
+
+/// Function parameter, storing its name and type annotation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: Type,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Struct {
+    /// Lines of the `///` doc comments preceding the declaration.
+    pub docs: Vec<String>,
+    pub name: String,
+    /// Names of the generic type parameters, as in `struct Foo<T>`.
+    pub generics: Vec<String>,
+    pub fields: Vec<Field>,
+}
+
+/// Struct field, storing its name and type annotation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+    pub span: Span,
+}
+
+/// Type annotation node, storing its kind and the span of its source text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Type {
+    pub kind: TypeKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeKind {
+    /// Named type, such as `String` or `bool`
+    Named(String),
+    /// Generic type applied to type arguments, such as `List<Int>`
+    Generic { name: String, args: Vec<Type> },
+}
+
+/// Statement node, storing its kind and the span of its source text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StmtKind {
     Let {
+        /// Lines of the `///` doc comments preceding the binding.
+        docs: Vec<String>,
         var: String,
+        /// The declared type of the binding, if annotated.
+        ty: Option<Type>,
         value: Box<Expr>,
     },
     Assignment {
@@ -20,12 +103,22 @@ pub enum Stmt {
     Block {
         stmts: Vec<Stmt>,
     },
+    Item(Item),
+    /// Expression evaluated for its value or side effects, as in `print(x);`
+    Expr(Expr),
+}
+
+/// Expression node, storing its kind and the span of its source text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
 }
 
 /// Abstract syntax tree based on expressions as a central language concept.
 #[derive(Clone, Debug, PartialEq)]
-pub enum Expr {
-    /// Literal value, such as `Integer`, `Double` or `String`
+pub enum ExprKind {
+    /// Literal value, such as `Integer`, `Double`, `String` or `Char`
     Literal(Literal),
     /// Identifier, storing its name.
     Identifier(String),
@@ -41,44 +134,165 @@ pub enum Expr {
     },
     /// Unary postfix operators
     PostfixOperator { op: Kind, expr: Box<Expr> },
+    /// Interpolated string, as in `"hello {name}"`, storing its literal
+    /// fragments and embedded expressions in source order.
+    Interpolated(Vec<StringPart>),
+}
+
+/// Part of an interpolated string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StringPart {
+    /// Literal text, with its escape sequences processed.
+    Literal(String),
+    /// Embedded expression, whose value is formatted into the string.
+    Expr(Expr),
+}
+
+impl Stmt {
+    /// Constructs a `Stmt` from its kind and the span it covers.
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl Expr {
+    /// Constructs an `Expr` from its kind and the span it covers.
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
 }
 
 /// Literals are representations of the language's primitive types.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Literal {
     /// `Integer` literal value
-    Integer(usize),
+    Integer(i64),
+    /// `Integer` literal value too large for an `i64`
+    #[cfg(feature = "bigint")]
+    BigInteger(num_bigint::BigInt),
+    /// `Integer` literal value with a type suffix, as in `10u8`
+    SuffixedInteger { value: i128, suffix: Suffix },
     /// `Double` literal value
     Double(f64),
+    /// `Double` literal value with a type suffix, as in `3.5f32`
+    SuffixedDouble { value: f64, suffix: Suffix },
     /// `String` literal value
     String(String),
+    /// `Char` literal value
+    Char(char),
+}
+
+/// Type suffix of a number literal, pinning it to a specific numeric type.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Suffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+}
+
+impl Suffix {
+    /// Returns the suffix spelled as `text`, such as `u8`.
+    pub fn parse(text: &str) -> Option<Self> {
+        Some(match text {
+            "i8" => Suffix::I8,
+            "i16" => Suffix::I16,
+            "i32" => Suffix::I32,
+            "i64" => Suffix::I64,
+            "u8" => Suffix::U8,
+            "u16" => Suffix::U16,
+            "u32" => Suffix::U32,
+            "u64" => Suffix::U64,
+            "f32" => Suffix::F32,
+            "f64" => Suffix::F64,
+            _ => return None,
+        })
+    }
+
+    /// Returns the range of values of an integer suffix's type, or `None` for
+    /// the `f32` and `f64` suffixes.
+    pub fn integer_range(self) -> Option<RangeInclusive<i128>> {
+        Some(match self {
+            Suffix::I8 => i8::MIN.into()..=i8::MAX.into(),
+            Suffix::I16 => i16::MIN.into()..=i16::MAX.into(),
+            Suffix::I32 => i32::MIN.into()..=i32::MAX.into(),
+            Suffix::I64 => i64::MIN.into()..=i64::MAX.into(),
+            Suffix::U8 => 0..=u8::MAX.into(),
+            Suffix::U16 => 0..=u16::MAX.into(),
+            Suffix::U32 => 0..=u32::MAX.into(),
+            Suffix::U64 => 0..=u64::MAX.into(),
+            Suffix::F32 | Suffix::F64 => return None,
+        })
+    }
 }
 
 impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl fmt::Display for ExprKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expr::Literal(value) => {
+            ExprKind::Literal(value) => {
                 write!(f, "{value}")
             }
-            Expr::Identifier(name) => {
+            ExprKind::Identifier(name) => {
                 write!(f, "{name}")
             }
-            Expr::FunctionCall { name, args } => {
+            ExprKind::FunctionCall { name, args } => {
                 write!(f, "{name}(")?;
                 for arg in args {
                     write!(f, "{arg},")?;
                 }
                 write!(f, ")")
             }
-            Expr::PrefixOperator { op, expr } => {
+            ExprKind::PrefixOperator { op, expr } => {
                 write!(f, "({op} {expr})")
             }
-            Expr::InfixOperator { op, lhs, rhs } => {
+            ExprKind::InfixOperator { op, lhs, rhs } => {
                 write!(f, "({lhs} {op} {rhs})")
             }
-            Expr::PostfixOperator { op, expr } => {
+            ExprKind::PostfixOperator { op, expr } => {
                 write!(f, "({expr} {op})")
             }
+            ExprKind::Interpolated(parts) => {
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        StringPart::Literal(string) => write_escaped(f, string)?,
+                        StringPart::Expr(expr) => write!(f, "{{{expr}}}")?,
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TypeKind::Named(name) => {
+                write!(f, "{name}")
+            }
+            TypeKind::Generic { name, args } => {
+                write!(f, "{name}<")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                write!(f, ">")
+            }
         }
     }
 }
@@ -89,12 +303,68 @@ impl fmt::Display for Literal {
             Literal::Integer(integer) => {
                 write!(f, "{}", integer)
             }
+            #[cfg(feature = "bigint")]
+            Literal::BigInteger(integer) => {
+                write!(f, "{}", integer)
+            }
+            Literal::SuffixedInteger { value, suffix } => {
+                write!(f, "{value}{suffix}")
+            }
             Literal::Double(double) => {
                 write!(f, "{}", double)
             }
+            Literal::SuffixedDouble { value, suffix } => {
+                write!(f, "{value}{suffix}")
+            }
             Literal::String(string) => {
-                write!(f, r#""{}""#, string)
+                write!(f, "\"")?;
+                write_escaped(f, string)?;
+                write!(f, "\"")
+            }
+            Literal::Char('\'') => write!(f, r"'\''"),
+            Literal::Char(c) => {
+                write!(f, "'")?;
+                write_escaped(f, c.encode_utf8(&mut [0; 4]))?;
+                write!(f, "'")
             }
         }
     }
 }
+
+impl fmt::Display for Suffix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Suffix::I8 => "i8",
+                Suffix::I16 => "i16",
+                Suffix::I32 => "i32",
+                Suffix::I64 => "i64",
+                Suffix::U8 => "u8",
+                Suffix::U16 => "u16",
+                Suffix::U32 => "u32",
+                Suffix::U64 => "u64",
+                Suffix::F32 => "f32",
+                Suffix::F64 => "f64",
+            }
+        )
+    }
+}
+
+/// Writes the contents of a string literal, escaping any characters that
+/// cannot appear in it verbatim.
+fn write_escaped(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    for c in string.chars() {
+        match c {
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            '\0' => write!(f, "\\0")?,
+            '\\' | '"' | '{' | '}' => write!(f, "\\{c}")?,
+            c if c.is_control() => write!(f, "\\u{{{:X}}}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::lexer::{Kind, Span, Token};
+
+/// Convenience alias for results produced by the parser.
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Error produced when the parser encounters input it cannot accept.
+///
+/// Each `ParseError` keeps the offending `Token`, the token kinds that would
+/// have been accepted in its place and a human-readable message, so callers
+/// can report the problem and carry on rather than aborting.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    /// The token found where the parser was unable to continue.
+    found: Token,
+    /// The token kinds that would have been accepted instead.
+    expected: Vec<Kind>,
+    /// Description of the error.
+    message: String,
+}
+
+impl ParseError {
+    /// Constructs a `ParseError` from the offending token, the expected token
+    /// kinds and a message.
+    pub fn new(found: Token, expected: Vec<Kind>, message: impl Into<String>) -> Self {
+        Self {
+            found,
+            expected,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the token that caused the error.
+    pub fn found(&self) -> Token {
+        self.found
+    }
+
+    /// Returns the `Span` of the token that caused the error.
+    pub fn span(&self) -> Span {
+        self.found.span()
+    }
+
+    /// Returns the token kinds that would have been accepted instead.
+    pub fn expected(&self) -> &[Kind] {
+        &self.expected
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for ParseError {}
@@ -3,90 +3,144 @@ use crate::parser::operator::Operator;
 use crate::tk;
 
 use super::ast;
-use super::Parser;
+use super::{ParseError, ParseResult, Parser};
 
 impl<'input, I> Parser<'input, I>
 where
     I: Iterator<Item = Token>,
 {
+    /// Parses an expression, returning a `ParseError` on invalid input.
     #[inline]
-    pub fn expression(&mut self) -> ast::Expr {
+    pub fn expression(&mut self) -> ParseResult<ast::Expr> {
         self.parse_expression(0)
     }
 
-    pub fn parse_expression(&mut self, binding_power: u8) -> ast::Expr {
-        let mut lhs = match self.peek() {
-            literal @ tk![integer] | literal @ tk![double] | literal @ tk![string] => {
-                let text = {
-                    // The calls on `self` need to be split as `next` takes a `&mut self`.
-                    let token = self.next().unwrap();
-                    self.text(token)
-                };
+    /// Parses an expression whose operators bind tighter than `binding_power`.
+    pub fn parse_expression(&mut self, binding_power: u8) -> ParseResult<ast::Expr> {
+        self.nested("Expression nested too deeply", |parser| {
+            parser.nested_expression(binding_power)
+        })
+    }
 
+    fn nested_expression(&mut self, binding_power: u8) -> ParseResult<ast::Expr> {
+        let start = self.peek_token().span();
+        let lhs = match self.peek() {
+            literal @ tk![integer]
+            | literal @ tk![double]
+            | literal @ tk![string]
+            | literal @ tk![char] => {
+                // The calls on `self` need to be split as `next` takes a `&mut self`.
+                let token = self.next().unwrap();
                 let inner = match literal {
-                    tk![integer] => ast::Literal::Integer(
-                        text.parse()
-                            .expect(&*format!("Invalid integer literal: `{text}`")),
-                    ),
-                    tk![double] => ast::Literal::Double(
-                        text.parse()
-                            .expect(&*format!("Invalid double literal: `{text}`")),
-                    ),
-                    tk![string] => ast::Literal::String(text[1..(text.len() - 1)].to_string()),
+                    tk![integer] => self.integer_literal(token)?,
+                    tk![double] => self.double_literal(token)?,
+                    tk![string] => ast::Literal::String(self.string_literal(token)?),
+                    tk![char] => ast::Literal::Char(self.char_literal(token)?),
                     _ => unreachable!("This case should never be reached."),
                 };
 
-                ast::Expr::Literal(inner)
+                ast::Expr::new(ast::ExprKind::Literal(inner), token.span())
             }
 
             tk![identifier] => {
                 let name = {
                     let token = self.next().unwrap();
-                    self.text(token).to_string()
+                    self.name(token)
                 };
 
                 // Classify the identifier; identifier or function call
                 if !self.at(tk!['(']) {
                     // Identifier
-                    ast::Expr::Identifier(name)
+                    ast::Expr::new(ast::ExprKind::Identifier(name), start)
                 } else {
                     // function call
                     let mut args = vec![];
-                    self.consume(tk!['(']);
+                    self.consume(tk!['('])?;
                     // function arguments
                     while !self.at(tk![')']) {
-                        let arg = self.parse_expression(0);
+                        let arg = self.parse_expression(0)?;
                         args.push(arg);
                         if self.at(tk![,]) {
-                            self.consume(tk![,]);
+                            self.consume(tk![,])?;
+                        } else {
+                            break;
                         }
                     }
 
-                    self.consume(tk![')']);
-                    ast::Expr::FunctionCall { name, args }
+                    self.consume(tk![')'])?;
+                    ast::Expr::new(
+                        ast::ExprKind::FunctionCall { name, args },
+                        self.span_from(start),
+                    )
                 }
             }
 
+            tk![string_start] => self.interpolated_string()?,
+
             tk!['('] => {
-                // Grouped expressions are parsed recursively
-                self.consume(tk!['(']);
-                let expr = self.parse_expression(0);
-                self.consume(tk![')']);
-                expr
+                // Grouped expressions are parsed recursively, with the span
+                // widened to include the parentheses.
+                self.consume(tk!['('])?;
+                let expr = self.parse_expression(0)?;
+                self.consume(tk![')'])?;
+                ast::Expr::new(expr.kind, self.span_from(start))
             }
 
             op @ tk![+] | op @ tk![-] | op @ tk![!] => {
-                self.consume(op);
-                let ((), right_bp) = op.prefix_binding_power();
-                let expr = self.parse_expression(right_bp);
-                ast::Expr::PrefixOperator {
-                    op,
-                    expr: Box::new(expr),
+                self.consume(op)?;
+                // The smallest `i64`, or value of a signed suffix's type, is
+                // only in range once negated, so it is parsed as a single
+                // literal.
+                let min = if op == tk![-] {
+                    self.min_integer_literal()
+                } else {
+                    None
+                };
+                if let Some(literal) = min {
+                    ast::Expr::new(ast::ExprKind::Literal(literal), self.span_from(start))
+                } else {
+                    let ((), right_bp) = op.prefix_binding_power();
+                    let expr = self.parse_expression(right_bp)?;
+                    ast::Expr::new(
+                        ast::ExprKind::PrefixOperator {
+                            op,
+                            expr: Box::new(expr),
+                        },
+                        self.span_from(start),
+                    )
                 }
             }
-            kind => panic!("Unknown start of expression: `{kind}`"),
+            kind => {
+                return Err(ParseError::new(
+                    self.peek_token(),
+                    vec![
+                        tk![integer],
+                        tk![double],
+                        tk![string],
+                        tk![char],
+                        tk![identifier],
+                        tk!['('],
+                        tk![+],
+                        tk![-],
+                        tk![!],
+                    ],
+                    format!("Unknown start of expression: `{kind}`"),
+                ))
+            }
         };
 
+        // Each operator nests the expression parsed so far one level deeper,
+        // so long chains such as `1 + 1 + ...` count toward the nesting limit
+        // as well.
+        let depth = self.depth;
+        let result = self.operators(lhs, binding_power);
+        self.depth = depth;
+        result
+    }
+
+    /// Parses the operators following `lhs`, whose binding power is above
+    /// `binding_power`, along with their right-hand operands.
+    fn operators(&mut self, mut lhs: ast::Expr, binding_power: u8) -> ParseResult<ast::Expr> {
         loop {
             let op = match self.peek() {
                 op @ tk![+]
@@ -103,9 +157,16 @@ where
                 | op @ tk![>]
                 | op @ tk![>=]
                 | op @ tk![!] => op,
-                tk![')'] | tk!['}'] | tk![,] | tk![;] => break,
+                tk![')'] | tk!['}'] | tk![,] | tk![;] | tk![=] => break,
+                tk![string_middle] | tk![string_end] => break,
                 tk![EOF] => break,
-                unknown => panic!("Unrecognized binary operator: `{unknown}`"),
+                unknown => {
+                    return Err(ParseError::new(
+                        self.peek_token(),
+                        vec![],
+                        format!("Unrecognized binary operator: `{unknown}`"),
+                    ))
+                }
             };
 
             if let Some((left_bp, ())) = op.postfix_binding_power() {
@@ -114,11 +175,16 @@ where
                     break;
                 }
 
-                self.consume(op);
-                lhs = ast::Expr::PostfixOperator {
-                    op,
-                    expr: Box::new(lhs),
-                };
+                self.descend("Expression nested too deeply")?;
+                let op_token = self.consume(op)?;
+                let span = lhs.span.merge(op_token.span());
+                lhs = ast::Expr::new(
+                    ast::ExprKind::PostfixOperator {
+                        op,
+                        expr: Box::new(lhs),
+                    },
+                    span,
+                );
                 // Parsed an operator; so continue the loop.
                 continue;
             }
@@ -129,13 +195,18 @@ where
                     break;
                 }
 
-                self.consume(op);
-                let rhs = self.parse_expression(right_bp);
-                lhs = ast::Expr::InfixOperator {
-                    op,
-                    lhs: Box::new(lhs),
-                    rhs: Box::new(rhs),
-                };
+                self.descend("Expression nested too deeply")?;
+                self.consume(op)?;
+                let rhs = self.parse_expression(right_bp)?;
+                let span = lhs.span.merge(rhs.span);
+                lhs = ast::Expr::new(
+                    ast::ExprKind::InfixOperator {
+                        op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    },
+                    span,
+                );
                 // Parsed an operator; so continue the loop.
                 continue;
             }
@@ -143,6 +214,6 @@ where
             break;
         }
 
-        lhs
+        Ok(lhs)
     }
 }
@@ -1,89 +1,191 @@
-use crate::lexer::Token;
-use crate::parser::{ast, Parser};
+use crate::lexer::{Span, Token};
+use crate::parser::{ast, ParseError, ParseResult, Parser};
 use crate::tk;
 
 impl<'input, I> Parser<'input, I>
 where
     I: Iterator<Item = Token>,
 {
-    pub fn statement(&mut self) -> ast::Stmt {
+    /// Parses a complete source file, up to and including `EOF`.
+    ///
+    /// Statements that fail to parse are skipped, so a `Program` is always
+    /// produced; the errors encountered along the way are available through
+    /// `Parser::errors`.
+    pub fn program(&mut self) -> ast::Program {
+        let docs = self.doc_comments(true);
+        let body = self.statements_until(tk![EOF]);
+        let span = Span::new(0, self.input.len());
+        ast::Program { docs, body, span }
+    }
+
+    /// Parses a single statement, returning a `ParseError` on invalid input.
+    ///
+    /// Errors inside of a block do not abort the statement; they are recovered
+    /// from and collected, making them available through `Parser::errors`.
+    pub fn statement(&mut self) -> ParseResult<ast::Stmt> {
+        self.nested("Statement nested too deeply", Self::nested_statement)
+    }
+
+    fn nested_statement(&mut self) -> ParseResult<ast::Stmt> {
+        // Doc comments are only attached to `let`, `fn` and `struct`, and are
+        // dropped like ordinary comments before any other statement.
+        let docs = self.doc_comments(false);
+
+        let start = self.peek_token().span();
         match self.peek() {
             tk![let] => {
-                self.consume(tk![let]);
-                let identifier = self.next().expect("Expected identifier after `let`");
-                assert_eq!(
-                    identifier.kind(),
-                    tk![identifier],
-                    "Expected identifier after `let`, but found: `{}`",
-                    identifier.kind()
-                );
-
-                let name = self.text(identifier).to_string();
-                self.consume(tk![=]);
-                let value = self.expression();
-                self.consume(tk![;]);
-                ast::Stmt::Let {
-                    var: name,
-                    value: Box::new(value),
-                }
+                self.consume(tk![let])?;
+                let identifier = self.expect_identifier(tk![let])?;
+
+                let name = self.name(identifier);
+                let ty = if self.at(tk![:]) {
+                    self.consume(tk![:])?;
+                    Some(self.parse_type(tk![:])?)
+                } else {
+                    None
+                };
+                self.consume(tk![=])?;
+                let value = self.expression()?;
+                self.consume(tk![;])?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Let {
+                        docs,
+                        var: name,
+                        ty,
+                        value: Box::new(value),
+                    },
+                    self.span_from(start),
+                ))
             }
 
             tk![identifier] => {
-                let identifier = self.next().unwrap();
-                let name = self.text(identifier).to_string();
-                self.consume(tk![=]);
-                let value = self.expression();
-                self.consume(tk![;]);
-                ast::Stmt::Assignment {
-                    var: name,
-                    value: Box::new(value),
+                // Assignments and expression statements both start with an
+                // identifier, so parse an expression and then classify it.
+                let expr = self.expression()?;
+                if !self.at(tk![=]) {
+                    self.consume(tk![;])?;
+                    return Ok(ast::Stmt::new(
+                        ast::StmtKind::Expr(expr),
+                        self.span_from(start),
+                    ));
                 }
+
+                let ast::ExprKind::Identifier(name) = expr.kind else {
+                    return Err(ParseError::new(
+                        self.peek_token(),
+                        vec![tk![;]],
+                        format!("Invalid left-hand side of assignment: `{expr}`"),
+                    ));
+                };
+                self.consume(tk![=])?;
+                let value = self.expression()?;
+                self.consume(tk![;])?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Assignment {
+                        var: name,
+                        value: Box::new(value),
+                    },
+                    self.span_from(start),
+                ))
+            }
+
+            tk![integer]
+            | tk![double]
+            | tk![string]
+            | tk![string_start]
+            | tk![char]
+            | tk!['(']
+            | tk![+]
+            | tk![-]
+            | tk![!] => {
+                let expr = self.expression()?;
+                self.consume(tk![;])?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Expr(expr),
+                    self.span_from(start),
+                ))
             }
 
             tk![if] => {
-                self.consume(tk![if]);
-                self.consume(tk!['(']);
-                let cond = self.expression();
-                self.consume(tk![')']);
-                assert!(
-                    self.at(tk!['{']),
-                    "Expected a block after an `if` statement."
-                );
-
-                let block = match self.statement() {
-                    ast::Stmt::Block { stmts } => stmts,
+                self.consume(tk![if])?;
+                self.consume(tk!['('])?;
+                let cond = self.expression()?;
+                self.consume(tk![')'])?;
+                if !self.at(tk!['{']) {
+                    return Err(ParseError::new(
+                        self.peek_token(),
+                        vec![tk!['{']],
+                        "Expected a block after an `if` statement.",
+                    ));
+                }
+
+                let block = match self.statement()?.kind {
+                    ast::StmtKind::Block { stmts } => stmts,
                     _ => unreachable!(),
                 };
 
                 let else_stmt = if self.at(tk![else]) {
-                    self.consume(tk![else]);
-                    assert!(
-                        self.at(tk![if]) || self.at(tk!['{']),
-                        "Expected a block or `if` statement after an `else` statement"
-                    );
-                    Some(Box::new(self.statement()))
+                    self.consume(tk![else])?;
+                    if !(self.at(tk![if]) || self.at(tk!['{'])) {
+                        return Err(ParseError::new(
+                            self.peek_token(),
+                            vec![tk![if], tk!['{']],
+                            "Expected a block or `if` statement after an `else` statement",
+                        ));
+                    }
+                    Some(Box::new(self.statement()?))
                 } else {
                     None
                 };
 
-                ast::Stmt::If {
-                    cond: Box::new(cond),
-                    body: block,
-                    else_stmt,
-                }
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::If {
+                        cond: Box::new(cond),
+                        body: block,
+                        else_stmt,
+                    },
+                    self.span_from(start),
+                ))
+            }
+
+            tk![fn] => {
+                let function = self.function(docs)?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Item(ast::Item::Function(function)),
+                    self.span_from(start),
+                ))
+            }
+
+            tk![struct] => {
+                let structure = self.structure(docs)?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Item(ast::Item::Struct(structure)),
+                    self.span_from(start),
+                ))
             }
 
             tk!['{'] => {
-                self.consume(tk!['{']);
-                let mut stmts = vec![];
-                while !self.at(tk!['}']) {
-                    stmts.push(self.statement());
-                }
-                self.consume(tk!['}']);
-                ast::Stmt::Block { stmts }
+                self.consume(tk!['{'])?;
+                let stmts = self.statements_until(tk!['}']);
+                self.consume(tk!['}'])?;
+                Ok(ast::Stmt::new(
+                    ast::StmtKind::Block { stmts },
+                    self.span_from(start),
+                ))
             }
 
-            kind => panic!("Unknown start of statement: `{kind}`"),
+            kind => Err(ParseError::new(
+                self.peek_token(),
+                vec![
+                    tk![let],
+                    tk![identifier],
+                    tk![if],
+                    tk!['{'],
+                    tk![fn],
+                    tk![struct],
+                ],
+                format!("Unknown start of statement: `{kind}`"),
+            )),
         }
     }
 }
@@ -0,0 +1,106 @@
+use crate::lexer::{Kind, Span, Token};
+use crate::parser::{ast, ParseResult, Parser};
+use crate::tk;
+
+impl<'input, I> Parser<'input, I>
+where
+    I: Iterator<Item = Token>,
+{
+    /// Parses a function declaration, starting at the `fn` keyword.
+    pub(crate) fn function(&mut self, docs: Vec<String>) -> ParseResult<ast::Function> {
+        self.consume(tk![fn])?;
+        let name = self.expect_identifier(tk![fn])?;
+        let name = self.name(name);
+
+        let mut params = vec![];
+        self.consume(tk!['('])?;
+        while !self.at(tk![')']) {
+            let after = if params.is_empty() { tk!['('] } else { tk![,] };
+            params.push(self.param(after)?);
+            if self.at(tk![,]) {
+                self.consume(tk![,])?;
+            } else {
+                break;
+            }
+        }
+        self.consume(tk![')'])?;
+
+        let ret = if self.at(tk![->]) {
+            self.consume(tk![->])?;
+            Some(self.parse_type(tk![->])?)
+        } else {
+            None
+        };
+
+        self.consume(tk!['{'])?;
+        let body = self.statements_until(tk!['}']);
+        self.consume(tk!['}'])?;
+
+        Ok(ast::Function {
+            docs,
+            name,
+            params,
+            ret,
+            body,
+        })
+    }
+
+    /// Parses a struct declaration, starting at the `struct` keyword.
+    pub(crate) fn structure(&mut self, docs: Vec<String>) -> ParseResult<ast::Struct> {
+        self.consume(tk![struct])?;
+        let name = self.expect_identifier(tk![struct])?;
+        let name = self.name(name);
+
+        let mut generics = vec![];
+        if self.at(tk![<]) {
+            self.consume(tk![<])?;
+            loop {
+                let after = if generics.is_empty() { tk![<] } else { tk![,] };
+                let param = self.expect_identifier(after)?;
+                generics.push(self.name(param));
+                if self.at(tk![,]) {
+                    self.consume(tk![,])?;
+                } else {
+                    break;
+                }
+            }
+            self.consume(tk![>])?;
+        }
+
+        let mut fields = vec![];
+        self.consume(tk!['{'])?;
+        while !self.at(tk!['}']) {
+            let after = if fields.is_empty() { tk!['{'] } else { tk![,] };
+            let (name, ty, span) = self.annotated_name(after)?;
+            fields.push(ast::Field { name, ty, span });
+            if self.at(tk![,]) {
+                self.consume(tk![,])?;
+            } else {
+                break;
+            }
+        }
+        self.consume(tk!['}'])?;
+
+        Ok(ast::Struct {
+            docs,
+            name,
+            generics,
+            fields,
+        })
+    }
+
+    /// Parses a single `name: Type` function parameter.
+    fn param(&mut self, after: Kind) -> ParseResult<ast::Param> {
+        let (name, ty, span) = self.annotated_name(after)?;
+        Ok(ast::Param { name, ty, span })
+    }
+
+    /// Parses a name followed by its type annotation, as in `name: Type`.
+    fn annotated_name(&mut self, after: Kind) -> ParseResult<(String, ast::Type, Span)> {
+        let identifier = self.expect_identifier(after)?;
+        let name = self.name(identifier);
+        self.consume(tk![:])?;
+        let ty = self.parse_type(tk![:])?;
+        Ok((name, ty, self.span_from(identifier.span())))
+    }
+}
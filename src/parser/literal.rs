@@ -0,0 +1,329 @@
+use crate::lexer::{suffix_start, Span, Token, TRIPLE_QUOTE};
+use crate::parser::{ast, ParseError, ParseResult, Parser};
+use crate::tk;
+
+impl<'input, I> Parser<'input, I>
+where
+    I: Iterator<Item = Token>,
+{
+    /// Converts an integer literal token into its value.
+    ///
+    /// Literals too large for an `i64`, or for the type of their suffix, are
+    /// an error. Unsuffixed literals too large for an `i64` are parsed into
+    /// `Literal::BigInteger` instead if the `bigint` feature is enabled.
+    pub(crate) fn integer_literal(&self, token: Token) -> ParseResult<ast::Literal> {
+        let (digits, radix, suffix) = self.integer_digits(token);
+        let out_of_range = |size: String| {
+            let text = self.text(token);
+            let message = format!("Integer literal out of range: `{text}` does not fit in {size}");
+            Err(ParseError::new(token, vec![], message))
+        };
+
+        if let Some(suffix) = suffix {
+            let range = suffix
+                .integer_range()
+                .expect("Integer suffixes have a range.");
+            return match i128::from_str_radix(&digits, radix) {
+                Ok(value) if range.contains(&value) => {
+                    Ok(ast::Literal::SuffixedInteger { value, suffix })
+                }
+                _ => out_of_range(format!("`{suffix}`")),
+            };
+        }
+
+        if let Ok(value) = i64::from_str_radix(&digits, radix) {
+            return Ok(ast::Literal::Integer(value));
+        }
+        #[cfg(feature = "bigint")]
+        if let Some(value) = num_bigint::BigInt::parse_bytes(digits.as_bytes(), radix) {
+            return Ok(ast::Literal::BigInteger(value));
+        }
+        out_of_range("64 bits".to_string())
+    }
+
+    /// Consumes an integer literal which is only in range once negated, such
+    /// as `9223372036854775808` or `128i8`, returning the negated literal.
+    /// Any other token is left in place.
+    pub(crate) fn min_integer_literal(&mut self) -> Option<ast::Literal> {
+        let token = self.peek_token();
+        if token.kind() != tk![integer] {
+            return None;
+        }
+        let (digits, radix, suffix) = self.integer_digits(token);
+        let min = match suffix {
+            Some(suffix) => *suffix.integer_range()?.start(),
+            None => i64::MIN.into(),
+        };
+        if min == 0 || u128::from_str_radix(&digits, radix) != Ok(min.unsigned_abs()) {
+            return None;
+        }
+        self.next();
+        Some(match suffix {
+            Some(suffix) => ast::Literal::SuffixedInteger { value: min, suffix },
+            None => ast::Literal::Integer(i64::MIN),
+        })
+    }
+
+    /// Returns the digits of an integer literal token along with their radix
+    /// and its suffix, if any, removing any `_` digit separators and `0x`,
+    /// `0o` or `0b` prefix.
+    fn integer_digits(&self, token: Token) -> (String, u32, Option<ast::Suffix>) {
+        let (text, suffix) = self.split_suffix(token);
+        let (digits, radix) = match text.get(..2) {
+            Some("0x") => (&text[2..], 16),
+            Some("0o") => (&text[2..], 8),
+            Some("0b") => (&text[2..], 2),
+            _ => (text, 10),
+        };
+        (digits.replace('_', ""), radix, suffix)
+    }
+
+    /// Converts a double literal token into its value, removing any `_` digit
+    /// separators.
+    ///
+    /// Literals too large to be represented as a finite `f64`, or `f32` for
+    /// the `f32` suffix, are an error.
+    pub(crate) fn double_literal(&self, token: Token) -> ParseResult<ast::Literal> {
+        let (text, suffix) = self.split_suffix(token);
+        let text = text.replace('_', "");
+        let value = match suffix {
+            Some(ast::Suffix::F32) => text.parse::<f32>().map(f64::from),
+            _ => text.parse::<f64>(),
+        };
+        let value = value.map_err(|_| {
+            let message = format!("Invalid double literal: `{}`", self.text(token));
+            ParseError::new(token, vec![], message)
+        })?;
+        if value.is_infinite() {
+            return Err(ParseError::new(
+                token,
+                vec![],
+                format!(
+                    "Double literal out of range: `{}` is too large",
+                    self.text(token)
+                ),
+            ));
+        }
+
+        Ok(match suffix {
+            Some(suffix) => ast::Literal::SuffixedDouble { value, suffix },
+            None => ast::Literal::Double(value),
+        })
+    }
+
+    /// Splits the text of a number literal token into its digits and its type
+    /// suffix, if any.
+    fn split_suffix(&self, token: Token) -> (&'input str, Option<ast::Suffix>) {
+        let text = self.text(token);
+        match suffix_start(text) {
+            Some(pos) => {
+                let suffix = ast::Suffix::parse(&text[pos..]);
+                debug_assert!(suffix.is_some(), "The lexer only accepts valid suffixes.");
+                (&text[..pos], suffix)
+            }
+            None => (text, None),
+        }
+    }
+
+    /// Decodes the contents of a string literal token.
+    ///
+    /// Escape sequences are processed in regular and triple-quoted strings,
+    /// but not in raw strings. Errors point at the offending escape sequence
+    /// rather than the whole string literal.
+    pub(crate) fn string_literal(&self, token: Token) -> ParseResult<String> {
+        let text = self.text(token);
+        let start = token.span().start();
+        if let Some(hashes) = text.strip_prefix('r') {
+            let hashes = hashes.bytes().take_while(|&b| b == b'#').count();
+            return Ok(text[(hashes + 2)..(text.len() - hashes - 1)].to_string());
+        }
+        if text.starts_with(TRIPLE_QUOTE) {
+            let quote = TRIPLE_QUOTE.len();
+            return dedent(&text[quote..(text.len() - quote)], start + quote);
+        }
+        unescape(&text[1..(text.len() - 1)], start + 1)
+    }
+
+    /// Decodes the character of a character literal token, processing its
+    /// escape sequence, if any.
+    pub(crate) fn char_literal(&self, token: Token) -> ParseResult<char> {
+        let text = self.text(token);
+        let value = unescape(&text[1..(text.len() - 1)], token.span().start() + 1)?;
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ParseError::new(
+                token,
+                vec![],
+                "Character literals must contain exactly one character",
+            )),
+        }
+    }
+
+    /// Parses an interpolated string, starting at its `"...{` fragment.
+    ///
+    /// The lexer splits interpolated strings into fragments around each
+    /// embedded expression, as in `"a {`, `} b {` and `} c"`.
+    pub(crate) fn interpolated_string(&mut self) -> ParseResult<ast::Expr> {
+        let start = self.consume(tk![string_start])?;
+        let mut parts = vec![];
+        let mut fragment = start;
+        loop {
+            // Each fragment is delimited by a single character on both ends.
+            let text = self.text(fragment);
+            let literal = unescape(&text[1..(text.len() - 1)], fragment.span().start() + 1)?;
+            if !literal.is_empty() {
+                parts.push(ast::StringPart::Literal(literal));
+            }
+            if fragment.kind() == tk![string_end] {
+                break;
+            }
+
+            parts.push(ast::StringPart::Expr(self.expression()?));
+            fragment = self.peek_token();
+            if !matches!(fragment.kind(), tk![string_middle] | tk![string_end]) {
+                return Err(ParseError::new(
+                    fragment,
+                    vec![tk![string_middle], tk![string_end]],
+                    format!(
+                        "Expected `}}` after embedded expression, but found: `{}`",
+                        fragment.kind()
+                    ),
+                ));
+            }
+            self.next();
+        }
+
+        Ok(ast::Expr::new(
+            ast::ExprKind::Interpolated(parts),
+            self.span_from(start.span()),
+        ))
+    }
+}
+
+/// Strips the common indentation from the lines of a triple-quoted string,
+/// then processes their escape sequences.
+///
+/// Indentation is only stripped when the opening quotes end their line, in
+/// which case that line break is dropped. A last line holding nothing but the
+/// indentation of the closing quotes is dropped too, but its indentation
+/// still counts towards the common indentation. Line breaks are normalized to
+/// `\n`.
+fn dedent(contents: &str, offset: usize) -> ParseResult<String> {
+    let Some(body) = contents
+        .strip_prefix('\n')
+        .or_else(|| contents.strip_prefix("\r\n"))
+    else {
+        return unescape(contents, offset);
+    };
+    let offset = offset + (contents.len() - body.len());
+
+    let mut lines = vec![];
+    let mut start = 0;
+    for line in body.split('\n') {
+        lines.push((start, line.strip_suffix('\r').unwrap_or(line)));
+        start += line.len() + 1;
+    }
+    let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let closing = lines
+        .last()
+        .filter(|(_, line)| line.trim().is_empty())
+        .copied();
+    let indent = lines
+        .iter()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .chain(&closing)
+        .map(|(_, line)| indentation(line))
+        .min()
+        .unwrap_or(0);
+    if closing.is_some() {
+        lines.pop();
+    }
+
+    let mut value = String::with_capacity(body.len());
+    for (i, (start, line)) in lines.into_iter().enumerate() {
+        if i > 0 {
+            value.push('\n');
+        }
+        let strip = indentation(line).min(indent);
+        value.push_str(&unescape(&line[strip..], offset + start + strip)?);
+    }
+    Ok(value)
+}
+
+/// Processes the escape sequences of a string literal's contents, which start
+/// at `offset` in the input source.
+fn unescape(contents: &str, offset: usize) -> ParseResult<String> {
+    let error = |start: usize, end: usize, message: String| {
+        let span = Span::new(offset + start, offset + end);
+        ParseError::new(Token::new(tk![string], span), vec![], message)
+    };
+
+    let mut value = String::with_capacity(contents.len());
+    let mut chars = contents.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let Some((pos, escape)) = chars.next() else {
+            return Err(error(
+                start,
+                start + 1,
+                "Unterminated escape sequence".into(),
+            ));
+        };
+        let decoded = match escape {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '{' => '{',
+            '}' => '}',
+            'u' => {
+                let rest = chars.as_str();
+                let Some((digits, _)) = rest.strip_prefix('{').and_then(|r| r.split_once('}'))
+                else {
+                    return Err(error(
+                        start,
+                        pos + 1,
+                        "Invalid unicode escape sequence".into(),
+                    ));
+                };
+                // Skip over the digits and both braces.
+                chars.nth(digits.len() + 1);
+                let end = pos + 1 + digits.len() + 2;
+
+                let code = u32::from_str_radix(digits, 16)
+                    .ok()
+                    .filter(|_| (1..=6).contains(&digits.len()));
+                match code.and_then(char::from_u32) {
+                    Some(c) => c,
+                    None => {
+                        return Err(error(
+                            start,
+                            end,
+                            format!(
+                                "Invalid unicode escape: `{}` is not a valid character",
+                                &contents[start..end]
+                            ),
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(error(
+                    start,
+                    pos + escape.len_utf8(),
+                    format!("Invalid escape sequence: `\\{}`", escape.escape_default()),
+                ))
+            }
+        };
+        value.push(decoded);
+    }
+    Ok(value)
+}
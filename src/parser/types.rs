@@ -0,0 +1,42 @@
+use crate::lexer::{Kind, Token};
+use crate::parser::{ast, ParseResult, Parser};
+use crate::tk;
+
+impl<'input, I> Parser<'input, I>
+where
+    I: Iterator<Item = Token>,
+{
+    /// Parses a type annotation expected to follow the `after` token.
+    ///
+    /// Types are either named, such as `Int`, or generic types applied to a
+    /// comma-separated list of type arguments, such as `Map<String, List<Int>>`.
+    pub(crate) fn parse_type(&mut self, after: Kind) -> ParseResult<ast::Type> {
+        let identifier = self.expect_identifier(after)?;
+        let name = self.name(identifier);
+        if !self.at(tk![<]) {
+            return Ok(ast::Type {
+                kind: ast::TypeKind::Named(name),
+                span: identifier.span(),
+            });
+        }
+
+        let mut args = vec![];
+        self.consume(tk![<])?;
+        loop {
+            let after = if args.is_empty() { tk![<] } else { tk![,] };
+            let arg = self.nested("Type nested too deeply", |parser| parser.parse_type(after))?;
+            args.push(arg);
+            if self.at(tk![,]) {
+                self.consume(tk![,])?;
+            } else {
+                break;
+            }
+        }
+        self.consume(tk![>])?;
+
+        Ok(ast::Type {
+            kind: ast::TypeKind::Generic { name, args },
+            span: self.span_from(identifier.span()),
+        })
+    }
+}
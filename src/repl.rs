@@ -0,0 +1,109 @@
+//! This module contains the read-eval-print loop (REPL) session state.
+
+use crate::interp::{Interpreter, RuntimeError, Value};
+use crate::lexer::{LexError, Lexer};
+use crate::parser::{ast, ParseError, Parser};
+use crate::tk;
+
+/// Result of feeding a line of input to the REPL.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// The input is incomplete, so the REPL is waiting for more lines.
+    Incomplete,
+    /// The input was executed, producing the values of its expression
+    /// statements.
+    Values(Vec<Value>),
+    /// The input contains invalid tokens.
+    LexErrors(Vec<LexError>),
+    /// The input failed to parse.
+    ParseErrors(Vec<ParseError>),
+    /// The input failed while executing. Statements before the failing one
+    /// have taken effect.
+    RuntimeError(RuntimeError),
+}
+
+/// Interactive session which buffers input until it forms complete statements.
+///
+/// Bindings made by earlier input persist across lines, as the same
+/// `Interpreter` is used for the whole session.
+#[derive(Default)]
+pub struct Repl {
+    interp: Interpreter,
+    /// Lines waiting for a continuation.
+    pending: String,
+    /// The most recently completed input.
+    source: String,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if previous lines are waiting for a continuation.
+    pub fn is_continuing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Returns the most recently completed input, which the spans of any
+    /// errors in the last `Outcome` refer to.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Feeds a line of input, executing the buffered lines once complete.
+    pub fn feed(&mut self, line: &str) -> Outcome {
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        if !is_complete(&self.pending) {
+            return Outcome::Incomplete;
+        }
+        self.source = std::mem::take(&mut self.pending);
+
+        let mut lexer = Lexer::new(&self.source);
+        lexer.by_ref().for_each(drop);
+        if !lexer.errors().is_empty() {
+            return Outcome::LexErrors(lexer.errors().to_vec());
+        }
+
+        let mut parser = Parser::new(&self.source);
+        let program = parser.program();
+        let errors = parser.take_errors();
+        if !errors.is_empty() {
+            return Outcome::ParseErrors(errors);
+        }
+
+        let mut values = vec![];
+        for stmt in &program.body {
+            match self.interp.execute(stmt) {
+                Ok(value) if matches!(stmt.kind, ast::StmtKind::Expr(_)) => values.push(value),
+                Ok(_) => {}
+                Err(error) => return Outcome::RuntimeError(error),
+            }
+        }
+        Outcome::Values(values)
+    }
+}
+
+/// Checks whether `input` forms complete statements.
+///
+/// Input is incomplete while it has unclosed `{` or `(` brackets, or when its
+/// last token does not end a statement.
+pub fn is_complete(input: &str) -> bool {
+    let mut depth = 0usize;
+    let mut last = None;
+    for token in Lexer::new(input) {
+        match token.kind() {
+            tk!['{'] | tk!['('] => depth += 1,
+            // Unbalanced closing brackets can never be completed, so they
+            // are left for the parser to report.
+            tk!['}'] | tk![')'] if depth == 0 => return true,
+            tk!['}'] | tk![')'] => depth -= 1,
+            tk![ws] | tk![comment] | tk![doc] | tk![EOF] => continue,
+            _ => {}
+        }
+        last = Some(token.kind());
+    }
+
+    depth == 0 && matches!(last, None | Some(tk![;]) | Some(tk!['}']))
+}
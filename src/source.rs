@@ -0,0 +1,245 @@
+//! This module contains utilities for mapping byte offsets to source locations.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Unit in which columns are counted.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Encoding {
+    /// Columns count UTF-8 code units, or bytes.
+    Utf8,
+    /// Columns count UTF-16 code units, as used by many editors.
+    Utf16,
+    /// Columns count UTF-32 code units, or characters.
+    Utf32,
+}
+
+/// A 1-based line and column position within the input source.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Character encoded as more than one UTF-8 code unit.
+#[derive(Copy, Clone, Debug)]
+struct WideChar {
+    /// Byte offset of the character from the start of its line.
+    start: usize,
+    len_utf8: usize,
+    len_utf16: usize,
+}
+
+impl WideChar {
+    /// Returns the number of code units of the character in `encoding`.
+    fn len(&self, encoding: Encoding) -> usize {
+        match encoding {
+            Encoding::Utf8 => self.len_utf8,
+            Encoding::Utf16 => self.len_utf16,
+            Encoding::Utf32 => 1,
+        }
+    }
+}
+
+/// Index of the line starts within the input source.
+///
+/// `LineIndex` converts the byte offsets stored in each `Span` to `LineCol`
+/// positions and back. Lines are found with a binary search, so lookups take
+/// `O(log n)` time in the number of lines.
+#[derive(Clone, Debug)]
+pub struct LineIndex {
+    /// Byte offset of the first character of each line.
+    line_starts: Vec<usize>,
+    /// Byte offset of the `\n` or `\r\n` terminating each line but the last.
+    line_ends: Vec<usize>,
+    /// Multi-byte characters of each line, keyed by 0-based line number.
+    wide_chars: HashMap<usize, Vec<WideChar>>,
+    /// The length of the input source in bytes.
+    len: usize,
+}
+
+impl LineIndex {
+    /// Constructs a `LineIndex` from the input source.
+    pub fn new(input: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut line_ends = vec![];
+        let mut wide_chars: HashMap<usize, Vec<WideChar>> = HashMap::new();
+        for (offset, c) in input.char_indices() {
+            if c == '\n' {
+                let crlf = input[..offset].ends_with('\r');
+                line_ends.push(offset - usize::from(crlf));
+                line_starts.push(offset + 1);
+            } else if c.len_utf8() > 1 {
+                let line = line_starts.len() - 1;
+                wide_chars.entry(line).or_default().push(WideChar {
+                    start: offset - line_starts[line],
+                    len_utf8: c.len_utf8(),
+                    len_utf16: c.len_utf16(),
+                });
+            }
+        }
+
+        Self {
+            line_starts,
+            line_ends,
+            wide_chars,
+            len: input.len(),
+        }
+    }
+
+    /// Returns the number of lines in the input source.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte offsets where the 1-based `line` starts and ends,
+    /// excluding its `\n` or `\r\n` line terminator.
+    pub fn line_bounds(&self, line: usize) -> Option<(usize, usize)> {
+        let index = line.checked_sub(1)?;
+        let start = *self.line_starts.get(index)?;
+        let end = self.line_ends.get(index).copied().unwrap_or(self.len);
+        Some((start, end))
+    }
+
+    /// Converts a byte offset into a line and column position.
+    ///
+    /// Offsets within a multi-byte character map to the column of that
+    /// character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is past the end of the input source.
+    pub fn line_col(&self, offset: usize, encoding: Encoding) -> LineCol {
+        assert!(offset <= self.len, "Offset {offset} is out of bounds");
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let col = offset - self.line_starts[line];
+        let col = match encoding {
+            Encoding::Utf8 => col,
+            encoding => self.utf8_to_wide_col(line, col, encoding),
+        };
+
+        LineCol {
+            line: line + 1,
+            col: col + 1,
+        }
+    }
+
+    /// Converts a line and column position into a byte offset.
+    ///
+    /// Returns `None` if the position is outside of the input source or does
+    /// not fall on a character boundary.
+    pub fn offset(&self, line_col: LineCol, encoding: Encoding) -> Option<usize> {
+        let (start, end) = self.line_bounds(line_col.line)?;
+        let line = line_col.line - 1;
+        let col = line_col.col.checked_sub(1)?;
+        let col = match encoding {
+            Encoding::Utf8 => col,
+            encoding => self.wide_to_utf8_col(line, col, encoding)?,
+        };
+
+        let inside_char = self
+            .wide_chars(line)
+            .iter()
+            .any(|c| c.start < col && col < c.start + c.len_utf8);
+        (start + col <= end && !inside_char).then_some(start + col)
+    }
+
+    /// Returns the multi-byte characters of the 0-based `line`.
+    fn wide_chars(&self, line: usize) -> &[WideChar] {
+        self.wide_chars.get(&line).map_or(&[], Vec::as_slice)
+    }
+
+    /// Converts a 0-based UTF-8 column into a UTF-16 or UTF-32 column.
+    fn utf8_to_wide_col(&self, line: usize, col: usize, encoding: Encoding) -> usize {
+        let mut result = col;
+        for c in self.wide_chars(line) {
+            if c.start >= col {
+                break;
+            }
+            if col < c.start + c.len_utf8 {
+                // The column points into this character, so map it to its start.
+                return result - (col - c.start);
+            }
+            result -= c.len_utf8 - c.len(encoding);
+        }
+        result
+    }
+
+    /// Converts a 0-based UTF-16 or UTF-32 column into a UTF-8 column.
+    fn wide_to_utf8_col(&self, line: usize, col: usize, encoding: Encoding) -> Option<usize> {
+        let mut shift = 0;
+        for c in self.wide_chars(line) {
+            let start = c.start - shift;
+            if col <= start {
+                break;
+            }
+            if col < start + c.len(encoding) {
+                // The column points between the surrogates of a character.
+                return None;
+            }
+            shift += c.len_utf8 - c.len(encoding);
+        }
+        Some(col + shift)
+    }
+}
+
+impl fmt::Display for LineCol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_col_lookups() {
+        let input = "let x = 1;\nlet y = 2;\r\n\nend";
+        let index = LineIndex::new(input);
+        assert_eq!(index.line_count(), 4);
+
+        let lc = |line, col| LineCol { line, col };
+        assert_eq!(index.line_col(0, Encoding::Utf8), lc(1, 1));
+        assert_eq!(index.line_col(10, Encoding::Utf8), lc(1, 11));
+        assert_eq!(index.line_col(11, Encoding::Utf8), lc(2, 1));
+        assert_eq!(index.line_col(23, Encoding::Utf8), lc(3, 1));
+        assert_eq!(index.line_col(input.len(), Encoding::Utf8), lc(4, 4));
+
+        for offset in 0..=input.len() {
+            let line_col = index.line_col(offset, Encoding::Utf8);
+            // The `\n` of a `\r\n` is past the end of its line.
+            let expected = (!input[..offset].ends_with('\r')).then_some(offset);
+            assert_eq!(index.offset(line_col, Encoding::Utf8), expected);
+        }
+        assert_eq!(index.offset(lc(1, 12), Encoding::Utf8), None);
+        assert_eq!(index.offset(lc(5, 1), Encoding::Utf8), None);
+        assert_eq!(index.offset(lc(1, 0), Encoding::Utf8), None);
+        assert_eq!(index.line_bounds(2), Some((11, 21)));
+        assert_eq!(index.offset(lc(2, 12), Encoding::Utf8), None);
+    }
+
+    #[test]
+    fn wide_character_columns() {
+        // `é` is 2 bytes in UTF-8 and `😀` is 4 bytes in UTF-8, 2 in UTF-16.
+        let input = "x\né😀y";
+        let index = LineIndex::new(input);
+        let y = input.find('y').unwrap();
+
+        assert_eq!(index.line_col(y, Encoding::Utf8).col, 7);
+        assert_eq!(index.line_col(y, Encoding::Utf16).col, 4);
+        assert_eq!(index.line_col(y - 4, Encoding::Utf16).col, 2);
+        // An offset within `😀` maps to the column of `😀` itself.
+        assert_eq!(index.line_col(y - 2, Encoding::Utf16).col, 2);
+
+        let lc = |col| LineCol { line: 2, col };
+        assert_eq!(index.offset(lc(4), Encoding::Utf16), Some(y));
+        assert_eq!(index.offset(lc(2), Encoding::Utf16), Some(y - 4));
+        assert_eq!(index.offset(lc(3), Encoding::Utf16), None);
+        assert_eq!(index.offset(lc(2), Encoding::Utf8), None);
+
+        assert_eq!(index.line_col(y, Encoding::Utf32).col, 3);
+        assert_eq!(index.offset(lc(3), Encoding::Utf32), Some(y));
+        assert_eq!(index.offset(lc(2), Encoding::Utf32), Some(y - 4));
+    }
+}
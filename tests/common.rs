@@ -1,5 +1,8 @@
+use transient::diagnostic::{Diagnostic, Label, Renderer};
+use transient::interp::{Interpreter, Value};
 use transient::lexer::*;
 use transient::parser::{ast, Parser};
+use transient::repl::{is_complete, Outcome, Repl};
 use transient::tk;
 
 /// Walks `$tokens` and compares them to the given token kinds.
@@ -58,6 +61,314 @@ mod tests {
         }
     }
 
+    #[test]
+    fn block_comments() {
+        let input = "a /* one /* two */ still one */ b /**/ c";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(
+            tokens,
+            [
+                tk![identifier],
+                tk![comment],
+                tk![identifier],
+                tk![comment],
+                tk![identifier],
+                tk![EOF],
+            ]
+        );
+        assert_eq!(&input[tokens[1].span()], "/* one /* two */ still one */");
+
+        let input = "x = 1; /* outer /* inner */\ny = 2;";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[tokens.len() - 2].kind(), tk![error]);
+        assert_eq!(tokens[tokens.len() - 2].span(), Span::new(7, input.len()));
+        assert_eq!(
+            lexer.errors(),
+            [LexError::new(
+                LexErrorKind::UnterminatedComment,
+                Span::new(7, 9)
+            )]
+        );
+
+        let mut parser = Parser::new("let x /* : Int */ = 1 /* + 2 */;");
+        assert!(parser.statement().is_ok());
+    }
+
+    #[test]
+    fn line_comment_endings() {
+        fn kinds(input: &str) -> Vec<Kind> {
+            Lexer::new(input).map(|t| t.kind()).collect()
+        }
+
+        assert_eq!(kinds("// last line"), [tk![comment], tk![EOF]]);
+        assert_eq!(kinds("/// last line"), [tk![doc], tk![EOF]]);
+        assert_eq!(
+            kinds("x // last line"),
+            [tk![identifier], tk![ws], tk![comment], tk![EOF]]
+        );
+        assert_eq!(kinds("//"), [tk![comment], tk![EOF]]);
+
+        let input = "// one\r\n/// two\r\nx";
+        let tokens = Lexer::new(input).tokenize();
+        assert_tokens!(tokens, [tk![comment], tk![doc], tk![identifier], tk![EOF],]);
+        assert_eq!(&input[tokens[0].span()], "// one\r\n");
+        assert_eq!(&input[tokens[1].span()], "/// two\r\n");
+
+        let mut parser = Parser::new("/// Windows.\r\nlet x = 1; // trailing");
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+        let ast::StmtKind::Let { docs, .. } = &program.body[0].kind else {
+            panic!("Expected a let binding");
+        };
+        assert_eq!(docs, &["Windows."]);
+        assert!(is_complete("let x = 1; // trailing"));
+    }
+
+    #[test]
+    fn interpolated_string_tokens() {
+        let input = r#""hi {name}, {n + 1} {f("{x}")}\{}""#;
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(
+            tokens,
+            [
+                tk![string_start],
+                tk![identifier],
+                tk![string_middle],
+                tk![identifier],
+                tk![+],
+                tk![integer],
+                tk![string_middle],
+                tk![identifier],
+                tk!['('],
+                tk![string_start],
+                tk![identifier],
+                tk![string_end],
+                tk![')'],
+                tk![string_end],
+                tk![EOF],
+            ]
+        );
+        assert_eq!(&input[tokens[0].span()], r#""hi {"#);
+        assert_eq!(&input[tokens[2].span()], "}, {");
+        assert_eq!(&input[tokens[13].span()], r#"}\{}""#);
+        assert!(lexer.errors().is_empty());
+
+        // Plain strings are unaffected, and escaped braces do not interpolate.
+        let tokens = Lexer::new(r#""a } b" "\{x\}""#).tokenize();
+        assert_tokens!(tokens, [tk![string], tk![ws], tk![string], tk![EOF],]);
+
+        let mut lexer = Lexer::new(r#""a {x} \q {y}""#);
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[2].kind(), tk![error]);
+        assert_eq!(tokens[3].kind(), tk![identifier]);
+        assert_eq!(tokens[4].kind(), tk![string_end]);
+        assert_eq!(lexer.errors()[0].kind(), LexErrorKind::InvalidEscape('q'));
+
+        // The braces of an invalid unicode escape do not interpolate either.
+        let mut lexer = Lexer::new(r#""\u{zz}""#);
+        let tokens = lexer.tokenize();
+        assert_tokens!(tokens, [tk![error], tk![EOF],]);
+        assert_eq!(
+            lexer.errors(),
+            [LexError::new(
+                LexErrorKind::InvalidUnicodeEscape,
+                Span::new(1, 7)
+            )]
+        );
+
+        let mut lexer = Lexer::new("let s = \"\\u{41\";\nlet t = 1;");
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(
+            tokens,
+            [
+                tk![let],
+                tk![identifier],
+                tk![=],
+                tk![error],
+                tk![;],
+                tk![let],
+                tk![identifier],
+                tk![=],
+                tk![integer],
+                tk![;],
+                tk![EOF],
+            ]
+        );
+        assert_eq!(
+            lexer.errors(),
+            [LexError::new(
+                LexErrorKind::InvalidUnicodeEscape,
+                Span::new(9, 11)
+            )]
+        );
+    }
+
+    #[test]
+    fn char_literals() {
+        let input = r"'a' '\n' '\'' '😀' '\u{1F600}' '\{'";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert!(tokens[..6].iter().all(|t| t.kind() == tk![char]));
+        assert!(lexer.errors().is_empty());
+
+        let chars = input
+            .split(' ')
+            .map(
+                |input| match Parser::new(input).expression().unwrap().kind {
+                    ast::ExprKind::Literal(ast::Literal::Char(c)) => c,
+                    kind => panic!("Expected a char literal, found: {kind:?}"),
+                },
+            )
+            .collect::<String>();
+        assert_eq!(chars, "a\n'😀😀{");
+        assert_eq!(ast::Literal::Char('\'').to_string(), r"'\''");
+        assert_eq!(ast::Literal::Char('\t').to_string(), r"'\t'");
+
+        let errors = |input| {
+            let mut lexer = Lexer::new(input);
+            lexer.by_ref().for_each(drop);
+            lexer
+                .errors()
+                .iter()
+                .map(|e| (e.kind(), e.span()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            errors("x = 'ab';"),
+            [(LexErrorKind::InvalidChar, Span::new(4, 8))]
+        );
+        assert_eq!(errors("''"), [(LexErrorKind::InvalidChar, Span::new(0, 2))]);
+        assert_eq!(
+            errors(r"'\q'"),
+            [(LexErrorKind::InvalidEscape('q'), Span::new(1, 3))]
+        );
+        assert_eq!(
+            errors("'a\nb"),
+            [(LexErrorKind::UnterminatedChar, Span::new(0, 1))]
+        );
+
+        let mut interp = Interpreter::new();
+        interp
+            .run(&Parser::new("let c = 'b' > 'a'; let s = \"a\" + 'b';").program())
+            .unwrap();
+        assert_eq!(interp.get("c"), Some(Value::Bool(true)));
+        assert_eq!(interp.get("s"), Some(Value::String("ab".to_string())));
+    }
+
+    #[test]
+    fn doc_comment_tokens() {
+        let input = "/// item\n//! module\n// plain\n//// plain\n///\n";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                tk![doc],
+                tk![doc],
+                tk![comment],
+                tk![comment],
+                tk![doc],
+                tk![EOF],
+            ]
+        );
+    }
+
+    #[test]
+    fn lexer_error_reasons() {
+        fn lex(input: &str) -> (Vec<Token>, Vec<LexError>) {
+            let mut lexer = Lexer::new(input);
+            let tokens = lexer
+                .tokenize()
+                .into_iter()
+                .filter(|t| t.kind() == tk![error])
+                .collect();
+            (tokens, lexer.errors().to_vec())
+        }
+
+        let (tokens, errors) = lex("let s = \"abc");
+        assert_eq!(tokens[0].span(), Span::new(8, 12));
+        assert_eq!(
+            errors,
+            [LexError::new(
+                LexErrorKind::UnterminatedString,
+                Span::new(8, 9)
+            )]
+        );
+
+        let (tokens, errors) = lex("\"a\\qb\\\"\" x");
+        assert_eq!(tokens[0].span(), Span::new(0, 8));
+        assert_eq!(
+            errors,
+            [LexError::new(
+                LexErrorKind::InvalidEscape('q'),
+                Span::new(2, 4)
+            )]
+        );
+        assert_eq!(errors[0].to_string(), "Invalid escape sequence: `\\q`");
+
+        let (tokens, errors) = lex("x = 123abc + 1.5e;");
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(
+            errors,
+            [
+                LexError::new(LexErrorKind::MalformedNumber, Span::new(4, 10)),
+                LexError::new(LexErrorKind::MissingExponent, Span::new(16, 17)),
+            ]
+        );
+
+        let (tokens, errors) = lex("x = .5abc;");
+        assert_eq!(tokens[0].span(), Span::new(4, 9));
+        assert_eq!(
+            errors,
+            [LexError::new(
+                LexErrorKind::MalformedNumber,
+                Span::new(4, 9)
+            )]
+        );
+
+        let (tokens, errors) = lex("{$$$$$$$+");
+        assert_eq!(tokens[0].span(), Span::new(1, 8));
+        assert_eq!(
+            errors,
+            [LexError::new(
+                LexErrorKind::UnexpectedCharacter('$'),
+                Span::new(1, 2)
+            )]
+        );
+
+        let (_, errors) = lex(r#""\u{zz} \u{1234567} \u12""#);
+        let spans = errors.iter().map(|e| e.span()).collect::<Vec<_>>();
+        assert!(errors
+            .iter()
+            .all(|e| e.kind() == LexErrorKind::InvalidUnicodeEscape));
+        assert_eq!(
+            spans,
+            [Span::new(1, 7), Span::new(8, 19), Span::new(20, 22)]
+        );
+
+        assert!(lex("let x = \"ok\\\\\" + 12;").1.is_empty());
+        assert!(lex(r#""\n\r\t\0\u{10FFFF}""#).1.is_empty());
+    }
+
     #[test]
     fn single_char_tokens_with_whitespace() {
         let input = "    +  - (.):  ";
@@ -101,6 +412,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arrow_token() {
+        let input = "-> - > -";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(tokens, [tk![->], tk![-], tk![>], tk![-], tk![EOF],]);
+    }
+
     #[test]
     fn keyword_tokens() {
         let input = "let fn struct if else";
@@ -227,61 +550,127 @@ struct Foo<T> {
     fn parse_expressions() {
         fn parse(input: &str) -> ast::Expr {
             let mut parser = Parser::new(input);
-            parser.expression()
+            parser.expression().unwrap()
         }
 
         let expr = parse("42");
-        assert_eq!(expr, ast::Expr::Literal(ast::Literal::Integer(42)));
+        assert_eq!(
+            expr,
+            ast::Expr::new(
+                ast::ExprKind::Literal(ast::Literal::Integer(42)),
+                Span::new(0, 2)
+            )
+        );
         let expr = parse("  2.7768  ");
-        assert_eq!(expr, ast::Expr::Literal(ast::Literal::Double(2.7768)));
-        let expr = parse("\"this_is_a_string\"");
         assert_eq!(
             expr,
-            ast::Expr::Literal(ast::Literal::String("this_is_a_string".to_string()))
+            ast::Expr::new(
+                ast::ExprKind::Literal(ast::Literal::Double(2.7768)),
+                Span::new(2, 8)
+            )
+        );
+        let expr = parse("\"this_is_a_string\"");
+        assert_eq!(
+            expr.kind,
+            ast::ExprKind::Literal(ast::Literal::String("this_is_a_string".to_string()))
         );
         let expr = parse(r#""this is 0123456789 also a string""#);
         assert_eq!(
-            expr,
-            ast::Expr::Literal(ast::Literal::String(
+            expr.kind,
+            ast::ExprKind::Literal(ast::Literal::String(
                 "this is 0123456789 also a string".to_string()
             ))
         );
         let expr = parse("BuildCommand");
-        assert_eq!(expr, ast::Expr::Identifier("BuildCommand".to_string()));
+        assert_eq!(
+            expr,
+            ast::Expr::new(
+                ast::ExprKind::Identifier("BuildCommand".to_string()),
+                Span::new(0, 12)
+            )
+        );
         let expr = parse("send  (  x, 2) ");
         assert_eq!(
             expr,
-            ast::Expr::FunctionCall {
-                name: "send".to_string(),
-                args: vec![
-                    ast::Expr::Identifier("x".to_string()),
-                    ast::Expr::Literal(ast::Literal::Integer(2)),
-                ],
-            }
+            ast::Expr::new(
+                ast::ExprKind::FunctionCall {
+                    name: "send".to_string(),
+                    args: vec![
+                        ast::Expr::new(
+                            ast::ExprKind::Identifier("x".to_string()),
+                            Span::new(9, 10)
+                        ),
+                        ast::Expr::new(
+                            ast::ExprKind::Literal(ast::Literal::Integer(2)),
+                            Span::new(12, 13)
+                        ),
+                    ],
+                },
+                Span::new(0, 14)
+            )
         );
         let expr = parse("!should_work");
         assert_eq!(
             expr,
-            ast::Expr::PrefixOperator {
-                op: tk![!],
-                expr: Box::new(ast::Expr::Identifier("should_work".to_string())),
-            }
+            ast::Expr::new(
+                ast::ExprKind::PrefixOperator {
+                    op: tk![!],
+                    expr: Box::new(ast::Expr::new(
+                        ast::ExprKind::Identifier("should_work".to_string()),
+                        Span::new(1, 12)
+                    )),
+                },
+                Span::new(0, 12)
+            )
         );
         let expr = parse("(-20)");
         assert_eq!(
             expr,
-            ast::Expr::PrefixOperator {
-                op: tk![-],
-                expr: Box::new(ast::Expr::Literal(ast::Literal::Integer(20))),
-            }
+            ast::Expr::new(
+                ast::ExprKind::PrefixOperator {
+                    op: tk![-],
+                    expr: Box::new(ast::Expr::new(
+                        ast::ExprKind::Literal(ast::Literal::Integer(20)),
+                        Span::new(2, 4)
+                    )),
+                },
+                Span::new(0, 5)
+            )
         );
     }
 
+    #[test]
+    fn node_spans() {
+        let input = "4 + -2! * (3)";
+        let expr = Parser::new(input).expression().unwrap();
+        assert_eq!(expr.span, Span::new(0, 13));
+        let ast::ExprKind::InfixOperator { lhs, rhs, .. } = expr.kind else {
+            panic!("Expected an infix operator");
+        };
+        assert_eq!(&input[lhs.span], "4");
+        assert_eq!(&input[rhs.span], "-2! * (3)");
+
+        let input = "if (x) {\n    let y = 1;\n} else {\n    y = 2;\n}";
+        let stmt = Parser::new(input).statement().unwrap();
+        assert_eq!(stmt.span, Span::new(0, input.len()));
+        let ast::StmtKind::If {
+            cond,
+            body,
+            else_stmt,
+        } = stmt.kind
+        else {
+            panic!("Expected an `if` statement");
+        };
+        assert_eq!(&input[cond.span], "x");
+        assert_eq!(&input[body[0].span], "let y = 1;");
+        assert_eq!(&input[else_stmt.unwrap().span], "{\n    y = 2;\n}");
+    }
+
     #[test]
     fn parse_binary_expressions() {
         fn parse(input: &str) -> ast::Expr {
             let mut parser = Parser::new(input);
-            parser.expression()
+            parser.expression().unwrap()
         }
 
         let expr = parse("4 + 2 * 3");
@@ -313,10 +702,1084 @@ struct Foo<T> {
     fn parse_postfix_expressions() {
         fn parse(input: &str) -> ast::Expr {
             let mut parser = Parser::new(input);
-            parser.expression()
+            parser.expression().unwrap()
         }
 
         let expr = parse("4 + -2! * 3");
         assert_eq!(expr.to_string(), "(4 + ((- (2 !)) * 3))");
     }
+
+    #[test]
+    fn parse_errors() {
+        let input = "let = 5;";
+        let mut parser = Parser::new(input);
+        let error = parser.statement().unwrap_err();
+        assert_eq!(error.found().kind(), tk![=]);
+        assert_eq!(error.span(), Span::new(4, 5));
+        assert_eq!(error.expected(), &[tk![identifier]]);
+
+        let input = "if (x) y = 1;";
+        let mut parser = Parser::new(input);
+        let error = parser.statement().unwrap_err();
+        assert_eq!(error.found().text(input), "y");
+        assert_eq!(error.expected(), &[tk!['{']]);
+
+        let input = "4 + ";
+        let mut parser = Parser::new(input);
+        let error = parser.expression().unwrap_err();
+        assert_eq!(error.found().kind(), tk![EOF]);
+        assert_eq!(error.span(), Span::new(4, 4));
+        assert_eq!(error.to_string(), "Unknown start of expression: `<EOF>`");
+
+        let input = "send(x;";
+        let mut parser = Parser::new(input);
+        let error = parser.expression().unwrap_err();
+        assert_eq!(error.found().kind(), tk![;]);
+        assert_eq!(error.expected(), &[tk![')']]);
+
+        // Deeply nested input is an error rather than a stack overflow.
+        let input = format!("let x = {}1{};", "(".repeat(1000), ")".repeat(1000));
+        let mut parser = Parser::new(&input);
+        let error = parser.statement().unwrap_err();
+        assert_eq!(error.message(), "Expression nested too deeply");
+        assert_eq!(error.found().kind(), tk!['(']);
+        let input = format!("let x = {}1;", "-".repeat(1000));
+        let error = Parser::new(&input).statement().unwrap_err();
+        assert_eq!(error.message(), "Expression nested too deeply");
+        let input = format!("let x: {}Int{} = 1;", "A<".repeat(1000), ">".repeat(1000));
+        let error = Parser::new(&input).statement().unwrap_err();
+        assert_eq!(error.message(), "Type nested too deeply");
+
+        // So are long operator chains, which nest the expression built so far.
+        let input = format!("let x = 1{};", " + 1".repeat(200_000));
+        let error = Parser::new(&input).statement().unwrap_err();
+        assert_eq!(error.message(), "Expression nested too deeply");
+        let input = format!("let x = 5{};", "!".repeat(200_000));
+        let error = Parser::new(&input).statement().unwrap_err();
+        assert_eq!(error.message(), "Expression nested too deeply");
+        let input = format!("let x = 1{};", " + 1".repeat(50));
+        assert!(Parser::new(&input).statement().is_ok());
+
+        let input = format!("{}{} let y = 2;", "{".repeat(1000), "}".repeat(1000));
+        let mut parser = Parser::new(&input);
+        let program = parser.program();
+        assert_eq!(program.body.len(), 2);
+        let errors = parser.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "Statement nested too deeply");
+    }
+
+    #[test]
+    fn parse_error_recovery() {
+        let input = r#"{
+    let = 5;
+    let x = 1;
+    y = + ;
+    if (x) { z = 2 } else { z = 3; }
+    let w = 4;
+}"#;
+        let mut parser = Parser::new(input);
+        let block = parser.statement().unwrap();
+        let stmts = match block.kind {
+            ast::StmtKind::Block { stmts } => stmts,
+            stmt => panic!("Expected a block, but found: {stmt:?}"),
+        };
+
+        assert_eq!(stmts.len(), 3);
+        assert!(matches!(&stmts[0].kind, ast::StmtKind::Let { var, .. } if var == "x"));
+        assert!(matches!(&stmts[1].kind, ast::StmtKind::If { body, .. } if body.is_empty()));
+        assert!(matches!(&stmts[2].kind, ast::StmtKind::Let { var, .. } if var == "w"));
+
+        let errors = parser.errors();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].found().kind(), tk![=]);
+        assert_eq!(errors[1].found().kind(), tk![;]);
+        assert_eq!(errors[2].found().kind(), tk!['}']);
+        assert_eq!(errors[2].expected(), &[tk![;]]);
+
+        assert_eq!(parser.take_errors().len(), 3);
+        assert!(parser.errors().is_empty());
+    }
+
+    #[test]
+    fn parse_program() {
+        let input = r#"
+// Testing a program
+let time = "Date time: \" test" + 3 / 2.4e-2^5;
+let strict = 0;
+if (!strict) {
+    time = time + ",";
+} else {
+    time = "";
+}
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.span, Span::new(0, input.len()));
+        assert_eq!(program.body.len(), 3);
+        assert!(matches!(&program.body[0].kind, ast::StmtKind::Let { var, .. } if var == "time"));
+        assert!(matches!(&program.body[1].kind, ast::StmtKind::Let { var, .. } if var == "strict"));
+        assert!(matches!(&program.body[2].kind, ast::StmtKind::If { .. }));
+
+        let mut parser = Parser::new("let x = ; } let y = 2;");
+        let program = parser.program();
+        assert_eq!(program.body.len(), 1);
+        assert_eq!(parser.errors().len(), 2);
+
+        let mut parser = Parser::new("");
+        assert!(parser.program().body.is_empty());
+    }
+
+    #[test]
+    fn parse_function_definition() {
+        let input = r#"
+// Testing a function
+fn build_project(repo_name: String, strict: bool) {
+    let time = "Date time: \" test" + 3 / 2.4e-2^5;
+    if (!strict) {
+        time = time + ",";
+    }
+}
+
+fn version() -> String {}
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.body.len(), 2);
+
+        let ast::StmtKind::Item(ast::Item::Function(build)) = &program.body[0].kind else {
+            panic!("Expected a function declaration");
+        };
+        assert_eq!(build.name, "build_project");
+        assert_eq!(build.params.len(), 2);
+        assert_eq!(build.params[0].name, "repo_name");
+        assert_eq!(
+            build.params[0].ty.kind,
+            ast::TypeKind::Named("String".to_string())
+        );
+        assert_eq!(&input[build.params[1].span], "strict: bool");
+        assert_eq!(build.ret, None);
+        assert_eq!(build.body.len(), 2);
+        assert!(input[program.body[0].span].starts_with("fn build_project("));
+
+        let ast::StmtKind::Item(ast::Item::Function(version)) = &program.body[1].kind else {
+            panic!("Expected a function declaration");
+        };
+        assert!(version.params.is_empty());
+        assert_eq!(&input[version.ret.as_ref().unwrap().span], "String");
+        assert!(version.body.is_empty());
+
+        let mut parser = Parser::new("fn broken(a: Int, b) {}");
+        parser.program();
+        let error = &parser.errors()[0];
+        assert_eq!(error.found().kind(), tk![')']);
+        assert_eq!(error.expected(), &[tk![:]]);
+    }
+
+    #[test]
+    fn parse_struct_definition() {
+        let input = r#"
+struct Project {
+    name: String,
+    strict: bool,
+}
+struct Empty {}
+struct Point { x: Double, y: Double }
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.body.len(), 3);
+
+        let ast::StmtKind::Item(ast::Item::Struct(project)) = &program.body[0].kind else {
+            panic!("Expected a struct declaration");
+        };
+        assert_eq!(project.name, "Project");
+        assert_eq!(project.fields.len(), 2);
+        assert_eq!(project.fields[0].name, "name");
+        assert_eq!(
+            project.fields[1].ty.kind,
+            ast::TypeKind::Named("bool".to_string())
+        );
+        assert_eq!(&input[project.fields[1].span], "strict: bool");
+
+        let ast::StmtKind::Item(ast::Item::Struct(empty)) = &program.body[1].kind else {
+            panic!("Expected a struct declaration");
+        };
+        assert!(empty.fields.is_empty());
+
+        let ast::StmtKind::Item(ast::Item::Struct(point)) = &program.body[2].kind else {
+            panic!("Expected a struct declaration");
+        };
+        assert_eq!(point.fields.len(), 2);
+        assert_eq!(
+            &input[program.body[2].span],
+            "struct Point { x: Double, y: Double }"
+        );
+
+        let mut parser = Parser::new("struct Broken { a: Int b: Int }");
+        parser.program();
+        let error = &parser.errors()[0];
+        assert_eq!(error.found().text("struct Broken { a: Int b: Int }"), "b");
+        assert_eq!(error.expected(), &[tk!['}']]);
+    }
+
+    #[test]
+    fn parse_doc_comments() {
+        let input = r#"//! Utilities for scripts.
+//!
+//!Second line.
+
+/// Adds two numbers.
+///
+/// Overflow is a runtime error.
+fn add(a: Int, b: Int) -> Int {
+    /// Inside of a body.
+    let sum = a + b;
+}
+
+// Not a doc comment.
+struct Point { x: Int, y: Int }
+
+/// The origin.
+struct Origin {}
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+        assert_eq!(program.docs, ["Utilities for scripts.", "", "Second line."]);
+
+        let ast::StmtKind::Item(ast::Item::Function(add)) = &program.body[0].kind else {
+            panic!("Expected a function");
+        };
+        assert_eq!(
+            add.docs,
+            ["Adds two numbers.", "", "Overflow is a runtime error."]
+        );
+        assert!(input[program.body[0].span].starts_with("fn add"));
+        let ast::StmtKind::Let { docs, .. } = &add.body[0].kind else {
+            panic!("Expected a let binding");
+        };
+        assert_eq!(docs, &["Inside of a body."]);
+
+        let ast::StmtKind::Item(ast::Item::Struct(point)) = &program.body[1].kind else {
+            panic!("Expected a struct");
+        };
+        assert!(point.docs.is_empty());
+        let ast::StmtKind::Item(ast::Item::Struct(origin)) = &program.body[2].kind else {
+            panic!("Expected a struct");
+        };
+        assert_eq!(origin.docs, ["The origin."]);
+
+        // Doc comments anywhere else are dropped like ordinary comments.
+        let input = "/// Dangling.\nx = 1;\n//! Misplaced.\nlet y = 1 +\n/// Inside.\n2;\nfn f(\n/// Param.\na: Int) {}";
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+        assert_eq!(program.body.len(), 3);
+        let ast::StmtKind::Let { docs, value, .. } = &program.body[1].kind else {
+            panic!("Expected a let binding");
+        };
+        assert!(docs.is_empty());
+        assert_eq!(value.to_string(), "(1 + 2)");
+        let ast::StmtKind::Item(ast::Item::Function(f)) = &program.body[2].kind else {
+            panic!("Expected a function");
+        };
+        assert!(f.docs.is_empty());
+    }
+
+    #[test]
+    fn parse_number_literals() {
+        fn parse(input: &str) -> ast::Literal {
+            match Parser::new(input).expression().unwrap().kind {
+                ast::ExprKind::Literal(literal) => literal,
+                kind => panic!("Expected a literal, found: {kind:?}"),
+            }
+        }
+
+        assert_eq!(parse("1_000_000"), ast::Literal::Integer(1_000_000));
+        assert_eq!(parse("0xFF_ff"), ast::Literal::Integer(0xFFFF));
+        assert_eq!(parse("0o7_55"), ast::Literal::Integer(0o755));
+        assert_eq!(parse("0b1010_0101"), ast::Literal::Integer(0b1010_0101));
+        assert_eq!(parse("0x_1"), ast::Literal::Integer(1));
+        assert_eq!(parse("1_000.000_5"), ast::Literal::Double(1_000.000_5));
+        assert_eq!(parse("0.5e1_0"), ast::Literal::Double(0.5e10));
+
+        let mut lexer = Lexer::new("0xFG 0b102 0o8 0x 1_");
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(
+            tokens,
+            [
+                tk![error],
+                tk![error],
+                tk![error],
+                tk![error],
+                tk![integer],
+                tk![EOF],
+            ]
+        );
+        assert!(lexer
+            .errors()
+            .iter()
+            .all(|e| e.kind() == LexErrorKind::MalformedNumber));
+    }
+
+    #[test]
+    fn parse_double_literals() {
+        fn parse(input: &str) -> Result<ast::Expr, transient::parser::ParseError> {
+            Parser::new(input).expression()
+        }
+
+        for (input, value) in [
+            ("1e10", 1e10),
+            ("2E-3", 2e-3),
+            ("1_0e+0_2", 1e3),
+            ("5.", 5.0),
+            (".5", 0.5),
+            (".25e1", 2.5),
+        ] {
+            assert_eq!(
+                parse(input).unwrap().kind,
+                ast::ExprKind::Literal(ast::Literal::Double(value)),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            parse("1e999").unwrap_err().message(),
+            "Double literal out of range: `1e999` is too large"
+        );
+
+        // A trailing dot does not swallow a range, field access or method call.
+        let tokens = Lexer::new("5.. 5.x 5._ x.5").tokenize();
+        assert_tokens!(
+            tokens,
+            [
+                tk![integer],
+                tk![.],
+                tk![.],
+                tk![ws],
+                tk![integer],
+                tk![.],
+                tk![identifier],
+                tk![ws],
+                tk![integer],
+                tk![.],
+                tk![_],
+                tk![ws],
+                tk![identifier],
+                tk![double],
+                tk![EOF],
+            ]
+        );
+
+        let mut lexer = Lexer::new("1e + 2.5E-;");
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[0].span(), Span::new(0, 2));
+        assert_eq!(tokens[4].span(), Span::new(5, 10));
+        assert_eq!(
+            lexer.errors(),
+            [
+                LexError::new(LexErrorKind::MissingExponent, Span::new(1, 2)),
+                LexError::new(LexErrorKind::MissingExponent, Span::new(8, 10)),
+            ]
+        );
+
+        let mut interp = Interpreter::new();
+        interp
+            .run(&Parser::new("let big = -inf; let odd = nan == nan;").program())
+            .unwrap();
+        assert_eq!(interp.get("big"), Some(Value::Double(f64::NEG_INFINITY)));
+        assert_eq!(interp.get("odd"), Some(Value::Bool(false)));
+    }
+
+    #[test]
+    fn parse_number_suffixes() {
+        fn parse(input: &str) -> Result<ast::Expr, transient::parser::ParseError> {
+            Parser::new(input).expression()
+        }
+        fn literal(input: &str) -> ast::Literal {
+            match parse(input).unwrap().kind {
+                ast::ExprKind::Literal(literal) => literal,
+                kind => panic!("Expected a literal, found: {kind:?}"),
+            }
+        }
+        let integer = |value, suffix| ast::Literal::SuffixedInteger { value, suffix };
+        let double = |value, suffix| ast::Literal::SuffixedDouble { value, suffix };
+
+        assert_eq!(literal("10u8"), integer(10, ast::Suffix::U8));
+        assert_eq!(literal("0xFF_u8"), integer(255, ast::Suffix::U8));
+        assert_eq!(literal("0b1i64"), integer(1, ast::Suffix::I64));
+        assert_eq!(literal("-128i8"), integer(-128, ast::Suffix::I8));
+        assert_eq!(
+            literal("18446744073709551615u64"),
+            integer(u64::MAX.into(), ast::Suffix::U64)
+        );
+        assert_eq!(literal("3.5f32"), double(3.5, ast::Suffix::F32));
+        assert_eq!(literal("0.1f32"), double(0.1f32.into(), ast::Suffix::F32));
+        assert_eq!(literal("1e3_f64"), double(1e3, ast::Suffix::F64));
+        assert_eq!(literal("2f64"), double(2.0, ast::Suffix::F64));
+        assert_eq!(literal("0x1f"), ast::Literal::Integer(0x1f));
+        assert_eq!(parse("-255u8").unwrap().to_string(), "(- 255u8)");
+
+        for (input, message) in [
+            (
+                "256u8",
+                "Integer literal out of range: `256u8` does not fit in `u8`",
+            ),
+            (
+                "128i8",
+                "Integer literal out of range: `128i8` does not fit in `i8`",
+            ),
+            (
+                "1e39f32",
+                "Double literal out of range: `1e39f32` is too large",
+            ),
+        ] {
+            assert_eq!(parse(input).unwrap_err().message(), message);
+        }
+
+        let mut lexer = Lexer::new("10u7 1.5f 0x1i 3ident 12abu8");
+        let tokens = lexer
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() == tk![error])
+            .collect::<Vec<_>>();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(
+            lexer.errors(),
+            [
+                LexError::new(LexErrorKind::InvalidSuffix, Span::new(2, 4)),
+                LexError::new(LexErrorKind::InvalidSuffix, Span::new(8, 9)),
+                LexError::new(LexErrorKind::InvalidSuffix, Span::new(13, 14)),
+                LexError::new(LexErrorKind::InvalidSuffix, Span::new(16, 21)),
+                LexError::new(LexErrorKind::MalformedNumber, Span::new(22, 28)),
+            ]
+        );
+
+        let mut interp = Interpreter::new();
+        let program = Parser::new("let x = 200u8 + 1; let y = 9223372036854775808u64;").program();
+        let error = interp.run(&program).unwrap_err();
+        assert_eq!(interp.get("x"), Some(Value::Integer(201)));
+        assert_eq!(
+            error.message(),
+            "Integer literal out of range: `9223372036854775808u64` does not fit in 64 bits"
+        );
+    }
+
+    #[test]
+    fn unicode_identifiers() {
+        let input = "let größe = 変数 + _x1 * ünïcödé;";
+        let tokens = Lexer::new(input)
+            .tokenize()
+            .into_iter()
+            .filter(|t| t.kind() != tk![ws])
+            .collect::<Vec<_>>();
+        assert_tokens!(
+            tokens,
+            [
+                tk![let],
+                tk![identifier],
+                tk![=],
+                tk![identifier],
+                tk![+],
+                tk![identifier],
+                tk![*],
+                tk![identifier],
+                tk![;],
+                tk![EOF],
+            ]
+        );
+        assert_eq!(tokens[1].text(input), "größe");
+        assert_eq!(tokens[3].text(input), "変数");
+
+        // Identifiers cannot start with a digit or contain symbols.
+        let mut lexer = Lexer::new("1größe €x");
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[0].kind(), tk![error]);
+        assert_eq!(
+            lexer.errors(),
+            [
+                LexError::new(LexErrorKind::MalformedNumber, Span::new(0, 8)),
+                LexError::new(LexErrorKind::UnexpectedCharacter('€'), Span::new(9, 12)),
+            ]
+        );
+
+        // A precomposed `é` and an `e` followed by a combining acute accent
+        // name the same binding.
+        let composed = "caf\u{E9}";
+        let decomposed = "cafe\u{301}";
+        let mut parser = Parser::new(decomposed);
+        assert_eq!(
+            parser.expression().unwrap().kind,
+            ast::ExprKind::Identifier(composed.to_string())
+        );
+
+        let mut interp = Interpreter::new();
+        let source = format!("let {composed} = 1; let sum = {decomposed} + 1;");
+        interp.run(&Parser::new(&source).program()).unwrap();
+        assert_eq!(interp.get("sum"), Some(Value::Integer(2)));
+    }
+
+    #[test]
+    fn parse_integer_range() {
+        fn parse(input: &str) -> Result<ast::Expr, transient::parser::ParseError> {
+            Parser::new(input).expression()
+        }
+
+        let max = parse("9223372036854775807").unwrap();
+        assert_eq!(
+            max.kind,
+            ast::ExprKind::Literal(ast::Literal::Integer(i64::MAX))
+        );
+        let min = parse("-9_223_372_036_854_775_808").unwrap();
+        assert_eq!(
+            min.kind,
+            ast::ExprKind::Literal(ast::Literal::Integer(i64::MIN))
+        );
+        assert_eq!(min.span, Span::new(0, 26));
+        assert_eq!(
+            parse("-0x8000_0000_0000_0000 + 1").unwrap().to_string(),
+            "(-9223372036854775808 + 1)"
+        );
+        assert_eq!(parse("-5").unwrap().to_string(), "(- 5)");
+
+        for input in ["9223372036854775808", "0x1_0000_0000_0000_0000"] {
+            let result = parse(input);
+            #[cfg(not(feature = "bigint"))]
+            assert_eq!(
+                result.unwrap_err().message(),
+                format!("Integer literal out of range: `{input}` does not fit in 64 bits")
+            );
+            #[cfg(feature = "bigint")]
+            assert!(matches!(
+                result.unwrap().kind,
+                ast::ExprKind::Literal(ast::Literal::BigInteger(_))
+            ));
+        }
+        // Only the smallest `i64` is in range once negated.
+        assert_eq!(
+            parse("-9223372036854775809").is_ok(),
+            cfg!(feature = "bigint")
+        );
+
+        let mut interp = Interpreter::new();
+        let program = Parser::new("let x = -9223372036854775808 - 1;").program();
+        let error = interp.run(&program).unwrap_err();
+        assert_eq!(error.message(), "Integer overflow in `-`");
+    }
+
+    #[test]
+    fn parse_string_escapes() {
+        fn parse(input: &str) -> Result<String, transient::parser::ParseError> {
+            let mut parser = Parser::new(input);
+            match parser.expression()?.kind {
+                ast::ExprKind::Literal(ast::Literal::String(string)) => Ok(string),
+                kind => panic!("Expected a string literal, found: {kind:?}"),
+            }
+        }
+
+        assert_eq!(parse(r#""a\"b\n""#).unwrap(), "a\"b\n");
+        assert_eq!(parse(r#""\t\r\\\0""#).unwrap(), "\t\r\\\0");
+        assert_eq!(parse(r#""\u{48}\u{1F600}\u{e9}""#).unwrap(), "H😀é");
+        assert_eq!(parse(r#""""#).unwrap(), "");
+
+        let input = r#"x + "ok \u{D800} no""#;
+        let mut parser = Parser::new(input);
+        let error = parser.expression().unwrap_err();
+        assert_eq!(&input[error.span()], r"\u{D800}");
+        assert_eq!(
+            error.message(),
+            r"Invalid unicode escape: `\u{D800}` is not a valid character"
+        );
+        let error = parse(r#""\u{110000}""#).unwrap_err();
+        assert_eq!(error.span(), Span::new(1, 11));
+
+        let literal = ast::Literal::String("say \"hi\"\n\u{7}".to_string());
+        assert_eq!(literal.to_string(), r#""say \"hi\"\n\u{7}""#);
+    }
+
+    #[test]
+    fn parse_raw_and_multi_line_strings() {
+        fn parse(input: &str) -> String {
+            let mut parser = Parser::new(input);
+            match parser.expression().unwrap().kind {
+                ast::ExprKind::Literal(ast::Literal::String(string)) => string,
+                kind => panic!("Expected a string literal, found: {kind:?}"),
+            }
+        }
+
+        assert_eq!(parse(r#"r"C:\path\n""#), r"C:\path\n");
+        assert_eq!(parse(r##"r#"{"key": "value"}"#"##), r#"{"key": "value"}"#);
+        assert_eq!(parse(r###"r##"a "# b"##"###), r##"a "# b"##);
+        assert_eq!(parse(r#"r"""#), "");
+
+        let input = "let json = \"\"\"\n    {\n      \"a\": \"\\t1\"\n\n    }\n    \"\"\";";
+        let mut parser = Parser::new(input);
+        let ast::StmtKind::Let { value, .. } = parser.statement().unwrap().kind else {
+            panic!("Expected a let binding");
+        };
+        assert_eq!(value.to_string(), r#""\{\n  \"a\": \"\t1\"\n\n\}""#);
+        assert_eq!(value.span, Span::new(11, input.len() - 1));
+
+        // The closing quotes' indentation counts, and CRLF becomes LF.
+        assert_eq!(
+            parse("\"\"\"\r\n    a\r\n      b\r\n  \"\"\""),
+            "  a\n    b"
+        );
+        assert_eq!(
+            parse("\"\"\"  one \"quoted\" line \"\"\""),
+            "  one \"quoted\" line "
+        );
+        assert_eq!(parse("\"\"\"\"\"\""), "");
+
+        let input = "\"\"\"\n    ok\n    \\u{D800}\n    \"\"\"";
+        let error = Parser::new(input).expression().unwrap_err();
+        assert_eq!(&input[error.span()], r"\u{D800}");
+
+        let mut lexer = Lexer::new("r#\"open\" x \"\"\"also open\"\" y");
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens[0].kind(), tk![error]);
+        assert_eq!(
+            lexer.errors(),
+            [LexError::new(
+                LexErrorKind::UnterminatedString,
+                Span::new(0, 3)
+            )]
+        );
+        assert!(!is_complete("let s = r\"unterminated;"));
+    }
+
+    #[test]
+    fn parse_interpolated_strings() {
+        let input = r#"print("hello {name}, you have {count + 1} items");"#;
+        let mut parser = Parser::new(input);
+        let stmt = parser.statement().unwrap();
+        let ast::StmtKind::Expr(call) = stmt.kind else {
+            panic!("Expected an expression statement");
+        };
+        let ast::ExprKind::FunctionCall { args, .. } = call.kind else {
+            panic!("Expected a function call");
+        };
+        let ast::ExprKind::Interpolated(parts) = &args[0].kind else {
+            panic!("Expected an interpolated string");
+        };
+        assert_eq!(parts.len(), 5);
+        assert_eq!(parts[0], ast::StringPart::Literal("hello ".to_string()));
+        assert!(matches!(&parts[1], ast::StringPart::Expr(e) if e.to_string() == "name"));
+        assert!(matches!(&parts[3], ast::StringPart::Expr(e) if e.to_string() == "(count + 1)"));
+        assert_eq!(&input[args[0].span], &input[6..input.len() - 2]);
+        assert_eq!(
+            args[0].to_string(),
+            r#""hello {name}, you have {(count + 1)} items""#
+        );
+
+        let mut parser = Parser::new(r#"x = "{a"#);
+        let error = parser.statement().unwrap_err();
+        assert_eq!(
+            error.message(),
+            "Expected `}` after embedded expression, but found: `<EOF>`"
+        );
+
+        let mut interp = Interpreter::new();
+        let mut parser =
+            Parser::new(r#"let n = 2; let s = "{n} + {n} = {n + n}, {"nested {n * 10}"}";"#);
+        interp.run(&parser.program()).unwrap();
+        assert_eq!(
+            interp.get("s"),
+            Some(Value::String("2 + 2 = 4, nested 20".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_type_annotations() {
+        let input = r#"
+struct Foo<T> {
+    bar: Bar<T>,
+}
+fn index(lookup: Map<String, List<Int>>, key: String) -> List<Int> {
+    let x: Int = 5;
+    let y = x;
+}
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+
+        let ast::StmtKind::Item(ast::Item::Struct(foo)) = &program.body[0].kind else {
+            panic!("Expected a struct declaration");
+        };
+        assert_eq!(foo.generics, vec!["T".to_string()]);
+        assert_eq!(
+            foo.fields[0].ty.kind,
+            ast::TypeKind::Generic {
+                name: "Bar".to_string(),
+                args: vec![ast::Type {
+                    kind: ast::TypeKind::Named("T".to_string()),
+                    span: Span::new(30, 31),
+                }],
+            }
+        );
+
+        let ast::StmtKind::Item(ast::Item::Function(index)) = &program.body[1].kind else {
+            panic!("Expected a function declaration");
+        };
+        let lookup = &index.params[0].ty;
+        assert_eq!(lookup.to_string(), "Map<String, List<Int>>");
+        assert_eq!(&input[lookup.span], "Map<String, List<Int>>");
+        assert_eq!(index.ret.as_ref().unwrap().to_string(), "List<Int>");
+
+        let ast::StmtKind::Let { ty, .. } = &index.body[0].kind else {
+            panic!("Expected a `let` statement");
+        };
+        assert_eq!(ty.as_ref().unwrap().to_string(), "Int");
+        let ast::StmtKind::Let { ty, .. } = &index.body[1].kind else {
+            panic!("Expected a `let` statement");
+        };
+        assert_eq!(ty, &None);
+
+        let mut parser = Parser::new("let x: List<> = 5;");
+        let error = parser.statement().unwrap_err();
+        assert_eq!(error.found().kind(), tk![>]);
+        assert_eq!(error.expected(), &[tk![identifier]]);
+    }
+
+    #[test]
+    fn parse_expression_statements() {
+        let input = "print(x, 2); -4; x = y;";
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+        assert!(
+            matches!(&program.body[0].kind, ast::StmtKind::Expr(expr) if expr.to_string() == "print(x,2,)")
+        );
+        assert!(matches!(&program.body[1].kind, ast::StmtKind::Expr(_)));
+        assert!(matches!(
+            &program.body[2].kind,
+            ast::StmtKind::Assignment { .. }
+        ));
+
+        let mut parser = Parser::new("f(x) = 2;");
+        let error = parser.statement().unwrap_err();
+        assert_eq!(error.found().kind(), tk![=]);
+    }
+
+    #[test]
+    fn interpret_program() {
+        let input = r#"
+let count = 0;
+let total = 1;
+fn bump(by: Int) {
+    count = count + by;
+}
+bump(2);
+bump(3);
+if (count > 4 && !false) {
+    let total = 100;
+    total = total + 1;
+} else {
+    total = -1;
+}
+let label = "count: " + count;
+let ratio = 7 / 2 + 1.5;
+let fact = 5!;
+"#;
+        let mut parser = Parser::new(input);
+        let program = parser.program();
+        assert!(parser.errors().is_empty());
+
+        let mut interp = Interpreter::new();
+        interp.run(&program).unwrap();
+        assert_eq!(interp.get("count"), Some(Value::Integer(5)));
+        // The `total` declared inside of the `if` block shadows the outer one.
+        assert_eq!(interp.get("total"), Some(Value::Integer(1)));
+        assert_eq!(
+            interp.get("label"),
+            Some(Value::String("count: 5".to_string()))
+        );
+        assert_eq!(interp.get("ratio"), Some(Value::Double(4.5)));
+        assert_eq!(interp.get("fact"), Some(Value::Integer(120)));
+    }
+
+    #[test]
+    fn interpret_runtime_errors() {
+        fn run(input: &str) -> transient::interp::RuntimeError {
+            let program = Parser::new(input).program();
+            Interpreter::new().run(&program).unwrap_err()
+        }
+
+        let error = run("let x = y + 1;");
+        assert_eq!(error.message(), "Undefined variable: `y`");
+        assert_eq!(error.span(), Span::new(8, 9));
+
+        let error = run("z = 1;");
+        assert_eq!(error.message(), "Cannot assign to undefined variable: `z`");
+
+        let error = run("let x = 1 / 0;");
+        assert_eq!(error.message(), "Division by zero");
+
+        let error = run("if (1) {}");
+        assert_eq!(
+            error.message(),
+            "Expected a Bool condition, but found: Integer"
+        );
+
+        let error = run("let x = true - 1;");
+        assert_eq!(error.message(), "Cannot apply `-` to Bool and Integer");
+        assert_eq!(error.span(), Span::new(8, 16));
+
+        let error = run("fn f(a: Int) {} f();");
+        assert_eq!(error.message(), "`f` expects 1 argument(s), but found: 0");
+
+        // Unbounded recursion is an error rather than a stack overflow, and
+        // leaves the interpreter usable.
+        let error = run("fn f(n: Int) { f(n); } f(1);");
+        assert_eq!(error.message(), "Maximum recursion depth exceeded");
+        // The limit is reached somewhere within the recursive call.
+        assert!(error.span().start() >= 15 && error.span().end() <= 19);
+        let mut interp = Interpreter::new();
+        let program = Parser::new(
+            "let calls = 0; fn f(n: Int) { if (n > 0) { calls = calls + 1; f(n - 1); } }",
+        )
+        .program();
+        interp.run(&program).unwrap();
+        let deep = Parser::new("f(1000);").program();
+        let error = interp.run(&deep).unwrap_err();
+        assert_eq!(error.message(), "Maximum recursion depth exceeded");
+        interp
+            .run(&Parser::new("calls = 0; f(50);").program())
+            .unwrap();
+        assert_eq!(interp.get("calls"), Some(Value::Integer(50)));
+
+        // Nested blocks and expressions count toward the limit too.
+        let input = format!(
+            "fn f(n: Int) {{ if (n > 0) {{ {} f(n - 1); {} }} }} f(120);",
+            "if (true) {".repeat(28),
+            "}".repeat(28)
+        );
+        let error = run(&input);
+        assert_eq!(error.message(), "Maximum recursion depth exceeded");
+
+        // The parser rejects chains this long, so the expression is built by
+        // hand.
+        let one = || {
+            ast::Expr::new(
+                ast::ExprKind::Literal(ast::Literal::Integer(1)),
+                Span::new(0, 1),
+            )
+        };
+        let mut sum = one();
+        for _ in 1..5000 {
+            let kind = ast::ExprKind::InfixOperator {
+                op: tk![+],
+                lhs: Box::new(sum),
+                rhs: Box::new(one()),
+            };
+            sum = ast::Expr::new(kind, Span::new(0, 1));
+        }
+        let error = Interpreter::new().evaluate(&sum).unwrap_err();
+        assert_eq!(error.message(), "Maximum recursion depth exceeded");
+
+        // The right-hand side of a short-circuiting operator is never evaluated.
+        let program = Parser::new("let x = false && missing;").program();
+        let mut interp = Interpreter::new();
+        interp.run(&program).unwrap();
+        assert_eq!(interp.get("x"), Some(Value::Bool(false)));
+    }
+
+    #[test]
+    fn command_line_interface() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        fn transient(command: &str, input: &str) -> (i32, String, String) {
+            let mut child = Command::new(env!("CARGO_BIN_EXE_transient"))
+                .arg(command)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("binary should be spawned");
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(input.as_bytes())
+                .unwrap();
+            let output = child.wait_with_output().unwrap();
+            (
+                output.status.code().unwrap(),
+                String::from_utf8(output.stdout).unwrap(),
+                String::from_utf8(output.stderr).unwrap(),
+            )
+        }
+
+        let (code, stdout, _) = transient("lex", "let x");
+        assert_eq!(code, 0);
+        assert!(stdout.starts_with("KeywordLet - { 0 .. 3 }\n"));
+        let (code, _, stderr) = transient("lex", "let $");
+        assert_eq!(code, 1);
+        assert!(stderr.starts_with("error: Unexpected character: `$`\n --> <stdin>:1:5\n"));
+        let (code, _, stderr) = transient("run", "let s = \"abc");
+        assert_eq!(code, 1);
+        assert!(stderr.starts_with("error: Unterminated string literal\n"));
+        assert!(stderr.ends_with("^ unterminated string starting here\n"));
+
+        let (code, stdout, _) = transient("parse", "let x = 1;");
+        assert_eq!(code, 0);
+        assert!(stdout.contains("Let {"));
+        let (code, _, stderr) = transient("parse", "let = 1;");
+        assert_eq!(code, 1);
+        assert!(stderr.starts_with(
+            "error: Expected identifier after `let`, but found: `=`\n --> <stdin>:1:5\n"
+        ));
+
+        let (code, stdout, _) = transient("run", "let x = 2; print(x * 3);");
+        assert_eq!(code, 0);
+        assert_eq!(stdout, "6\n");
+        let (code, _, stderr) = transient("run", "print(y);");
+        assert_eq!(code, 1);
+        assert!(stderr.starts_with("error: Undefined variable: `y`\n --> <stdin>:1:7\n"));
+
+        let (code, stdout, _) = transient("repl", "'a';\n\"s\";\n1;\n");
+        assert_eq!(code, 0);
+        assert_eq!(stdout, ">> 'a'\n>> \"s\"\n>> 1\n>> \n");
+
+        let (code, _, _) = transient("unknown", "");
+        assert_eq!(code, 2);
+    }
+
+    #[test]
+    fn repl_sessions() {
+        assert!(is_complete(""));
+        assert!(is_complete("let x = 1;"));
+        assert!(is_complete("if (x) { y = 1; }"));
+        assert!(is_complete("} oops"));
+        assert!(!is_complete("let x = 1"));
+        assert!(!is_complete("fn f() {"));
+        assert!(!is_complete("print(1,"));
+
+        let mut repl = Repl::new();
+        assert_eq!(repl.feed("let x = 2;"), Outcome::Values(vec![]));
+        assert_eq!(repl.feed("fn bump() {"), Outcome::Incomplete);
+        assert!(repl.is_continuing());
+        assert_eq!(repl.feed("    x = x + 1;"), Outcome::Incomplete);
+        assert_eq!(repl.feed("}"), Outcome::Values(vec![]));
+        assert!(!repl.is_continuing());
+        assert_eq!(repl.feed("bump(); x"), Outcome::Incomplete);
+        assert_eq!(
+            repl.feed("* 10;"),
+            Outcome::Values(vec![Value::Unit, Value::Integer(30)])
+        );
+
+        let Outcome::ParseErrors(errors) = repl.feed("let = 1;") else {
+            panic!("Expected parse errors");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(repl.source(), "let = 1;\n");
+
+        let Outcome::LexErrors(errors) = repl.feed("let y = 1$;") else {
+            panic!("Expected lex errors");
+        };
+        assert_eq!(errors[0].span(), Span::new(9, 10));
+
+        let Outcome::RuntimeError(error) = repl.feed("x = y;") else {
+            panic!("Expected a runtime error");
+        };
+        assert_eq!(&repl.source()[error.span()], "y");
+        assert_eq!(repl.feed("x;"), Outcome::Values(vec![Value::Integer(3)]));
+    }
+
+    #[test]
+    fn render_diagnostics() {
+        let input = "let x = 1;\n{\n    x = 2 }\n";
+        let mut parser = Parser::new(input);
+        parser.program();
+        let renderer = Renderer::new("main.tr", input);
+        let rendered = renderer.render(&Diagnostic::from(&parser.errors()[0]));
+        assert_eq!(
+            rendered,
+            "\
+error: Found `}`, but expected: `;`
+ --> main.tr:3:11
+  |
+3 |     x = 2 }
+  |           ^ expected `;`
+"
+        );
+
+        let input = "let x = 1;\nlet y = 2;\n\n\nlet x = 3;\n";
+        let diagnostic = Diagnostic::warning("`x` is declared twice")
+            .with_label(Label::secondary(Span::new(4, 5), "first declared here"))
+            .with_label(Label::primary(Span::new(28, 29), "redeclared here"))
+            .with_note("shadowing hides the first declaration");
+        let rendered = Renderer::new("main.tr", input).render(&diagnostic);
+        assert_eq!(
+            rendered,
+            "\
+warning: `x` is declared twice
+ --> main.tr:5:5
+  |
+1 | let x = 1;
+  |     - first declared here
+...
+5 | let x = 3;
+  |     ^ redeclared here
+  = note: shadowing hides the first declaration
+"
+        );
+
+        let colored = Renderer::new("main.tr", input)
+            .with_color(true)
+            .render(&diagnostic);
+        assert!(colored.starts_with("\x1b[1;33mwarning:\x1b[0m"));
+
+        // Lexer error tokens render through the same path.
+        let input = "let $$ = 2;";
+        let error = Lexer::new(input)
+            .find(|token| token.kind() == tk![error])
+            .unwrap();
+        let diagnostic = Diagnostic::error("Unrecognized input")
+            .with_label(Label::primary(error.span(), "not a valid token"));
+        assert!(Renderer::new("main.tr", input)
+            .render(&diagnostic)
+            .ends_with("1 | let $$ = 2;\n  |     ^^ not a valid token\n"));
+
+        // Columns count characters rather than bytes, and underlines follow
+        // the display width of the text.
+        let input = "let größe = 1 $;";
+        let mut lexer = Lexer::new(input);
+        lexer.by_ref().for_each(drop);
+        let rendered =
+            Renderer::new("main.tr", input).render(&Diagnostic::from(&lexer.errors()[0]));
+        assert_eq!(
+            rendered,
+            "\
+error: Unexpected character: `$`
+ --> main.tr:1:15
+  |
+1 | let größe = 1 $;
+  |               ^ not a valid token
+"
+        );
+
+        let input = "let 変数 = 変数;";
+        let diagnostic = Diagnostic::error("Undefined variable: `変数`")
+            .with_label(Label::primary(Span::new(13, 19), "not defined yet"));
+        assert_eq!(
+            Renderer::new("main.tr", input).render(&diagnostic),
+            "\
+error: Undefined variable: `変数`
+ --> main.tr:1:10
+  |
+1 | let 変数 = 変数;
+  |            ^^^^ not defined yet
+"
+        );
+    }
 }